@@ -5,23 +5,127 @@
 
 // Prefer importing stable_mir over internal rustc constructs to make this file more readable.
 
-use rustc_middle::ty::{self as rustc_ty, Const as InternalConst, Ty as InternalTy, TyCtxt};
-use rustc_span::Symbol;
-use stable_mir::abi::Layout;
+use std::ops::ControlFlow;
+
+use rustc_hir::def::DefKind;
+use rustc_middle::ty::{
+    self as rustc_ty, Const as InternalConst, ExistentialPredicateStableCmpExt, Ty as InternalTy,
+    TyCtxt, TypeSuperVisitable, TypeVisitable, TypeVisitableExt, Upcast,
+};
+use stable_mir::abi::{
+    AddressSpace, CallConvention, FieldsShape, FloatLength, IntegerLength, IntegerType, Layout,
+    Primitive, ReprOptions, Scalar, TagEncoding, VariantsShape, WrappingRange,
+};
 use stable_mir::mir::alloc::AllocId;
-use stable_mir::mir::mono::{Instance, MonoItem, StaticDef};
-use stable_mir::mir::{BinOp, Mutability, Place, ProjectionElem, Safety, UnOp};
+use stable_mir::mir::mono::{Instance, InstanceKind, MonoItem, StaticDef};
+use stable_mir::mir::{
+    BinOp, Body, BorrowKind, ConstOperand, CoroutineDesugaring, CoroutineKind, CoroutineSource,
+    FakeBorrowKind, MutBorrowKind, Mutability, Place, ProjectionElem, Safety, SourceInfo,
+    SwitchTargets, UnOp, VarDebugInfo, VarDebugInfoContents, VarDebugInfoFragment, Variance,
+};
+use stable_mir::target::MachineSize;
 use stable_mir::ty::{
-    Abi, AdtDef, Binder, BoundRegionKind, BoundTyKind, BoundVariableKind, ClosureKind, DynKind,
-    ExistentialPredicate, ExistentialProjection, ExistentialTraitRef, FloatTy, FnSig,
-    GenericArgKind, GenericArgs, IndexedVal, IntTy, MirConst, Movability, Pattern, Region, RigidTy,
-    Span, TermKind, TraitRef, Ty, TyConst, UintTy, VariantDef, VariantIdx,
+    Abi, AdtDef, AdtKind, AliasKind, AliasTerm, AliasTy, Binder, BoundRegion, BoundRegionKind,
+    BoundTy, BoundTyKind, BoundVariableKind, ClauseKind, ClosureDef, ClosureKind, ConstantKind,
+    CoroutineDef, DynKind, ExistentialPredicate, ExistentialProjection, ExistentialTraitRef,
+    FloatTy, FnSig, GenericArgKind, GenericArgs, GenericParamDef, Generics, ImplPolarity,
+    IndexedVal, IntTy, MirConst, Movability, OutlivesPredicate, ParamEnv, Pattern,
+    PredicatePolarity, ProjectionPredicate, Region, RegionKind, Reveal, RigidTy, Span, TermKind,
+    TraitPredicate, TraitRef, Ty, TyConst, TyConstKind, TyKind, UintTy, VariantDef, VariantIdx,
 };
-use stable_mir::{CrateItem, CrateNum, DefId};
+use stable_mir::{CrateItem, CrateNum, DefId, Error, Symbol};
 
-use super::RustcInternal;
+use super::{internal_align, IntoInternal, RustcInternal, RustcInternalChecked};
 use crate::rustc_smir::Tables;
 
+/// Implement `RustcInternal` for a stable id that implements `IndexedVal` by looking up the
+/// internal value stored at the given field of `Tables`.
+///
+/// Values whose type carries the `'tcx` lifetime must be lifted to the given `TyCtxt`, which is
+/// requested by the trailing `lift`.
+macro_rules! internal_from_table {
+    ($stable:ty => $internal:ty, $field:ident) => {
+        impl RustcInternal for $stable {
+            type T<'tcx> = $internal;
+            fn internal<'tcx>(&self, tables: &mut Tables<'_>, _tcx: TyCtxt<'tcx>) -> Self::T<'tcx> {
+                tables.$field[*self]
+            }
+        }
+    };
+    ($stable:ty => $internal:ty, $field:ident, lift) => {
+        impl RustcInternal for $stable {
+            type T<'tcx> = $internal;
+            fn internal<'tcx>(&self, tables: &mut Tables<'_>, tcx: TyCtxt<'tcx>) -> Self::T<'tcx> {
+                tcx.lift(tables.$field[*self]).unwrap()
+            }
+        }
+    };
+}
+
+internal_from_table!(DefId => rustc_span::def_id::DefId, def_ids, lift);
+internal_from_table!(Ty => InternalTy<'tcx>, types, lift);
+internal_from_table!(Span => rustc_span::Span, spans);
+internal_from_table!(AllocId => rustc_middle::mir::interpret::AllocId, alloc_ids, lift);
+internal_from_table!(Layout => rustc_target::abi::Layout<'tcx>, layouts, lift);
+
+/// Implement `RustcInternal` for stable constructs whose conversion needs neither the tables nor
+/// the type context by delegating to their `IntoInternal` implementation.
+macro_rules! internal_from_pure {
+    ($($stable:ty),+ $(,)?) => {
+        $(
+            impl RustcInternal for $stable {
+                type T<'tcx> = <$stable as IntoInternal>::T;
+                fn internal<'tcx>(
+                    &self,
+                    _tables: &mut Tables<'_>,
+                    _tcx: TyCtxt<'tcx>,
+                ) -> Self::T<'tcx> {
+                    self.to_internal()
+                }
+            }
+        )+
+    };
+}
+
+internal_from_pure!(
+    IntTy,
+    UintTy,
+    FloatTy,
+    Mutability,
+    Movability,
+    Safety,
+    BinOp,
+    UnOp,
+    Variance,
+    CrateNum,
+    CoroutineKind,
+    CoroutineDesugaring,
+    CoroutineSource,
+    VariantIdx,
+    DynKind,
+    Reveal,
+    PredicatePolarity,
+    ImplPolarity,
+    AliasKind,
+    ClosureKind,
+    AdtKind,
+    Abi,
+    MachineSize,
+    FieldsShape,
+    TagEncoding,
+    IntegerType,
+    IntegerLength,
+    Scalar,
+    WrappingRange,
+    Primitive,
+    FloatLength,
+    AddressSpace,
+    SwitchTargets,
+    BorrowKind,
+    MutBorrowKind,
+    FakeBorrowKind,
+);
+
 impl RustcInternal for CrateItem {
     type T<'tcx> = rustc_span::def_id::DefId;
     fn internal<'tcx>(&self, tables: &mut Tables<'_>, tcx: TyCtxt<'tcx>) -> Self::T<'tcx> {
@@ -29,17 +133,17 @@ fn internal<'tcx>(&self, tables: &mut Tables<'_>, tcx: TyCtxt<'tcx>) -> Self::T<
     }
 }
 
-impl RustcInternal for CrateNum {
-    type T<'tcx> = rustc_span::def_id::CrateNum;
-    fn internal<'tcx>(&self, _tables: &mut Tables<'_>, _tcx: TyCtxt<'tcx>) -> Self::T<'tcx> {
+impl IntoInternal for CrateNum {
+    type T = rustc_span::def_id::CrateNum;
+    fn to_internal(&self) -> Self::T {
         rustc_span::def_id::CrateNum::from_usize(*self)
     }
 }
 
-impl RustcInternal for DefId {
-    type T<'tcx> = rustc_span::def_id::DefId;
-    fn internal<'tcx>(&self, tables: &mut Tables<'_>, tcx: TyCtxt<'tcx>) -> Self::T<'tcx> {
-        tcx.lift(tables.def_ids[*self]).unwrap()
+impl RustcInternal for Symbol {
+    type T<'tcx> = rustc_span::Symbol;
+    fn internal<'tcx>(&self, _tables: &mut Tables<'_>, _tcx: TyCtxt<'tcx>) -> Self::T<'tcx> {
+        rustc_span::Symbol::intern(self)
     }
 }
 
@@ -50,6 +154,234 @@ fn internal<'tcx>(&self, tables: &mut Tables<'_>, tcx: TyCtxt<'tcx>) -> Self::T<
     }
 }
 
+impl RustcInternalChecked<DefId> for GenericArgs {
+    type T<'tcx> = rustc_ty::GenericArgsRef<'tcx>;
+
+    /// Check that these arguments match the number and kind of the generic parameters of `def`.
+    fn internal_checked<'tcx>(
+        &self,
+        def: &DefId,
+        tables: &mut Tables<'_>,
+        tcx: TyCtxt<'tcx>,
+    ) -> Result<Self::T<'tcx>, Error> {
+        let def_id = def.internal(tables, tcx);
+        let args = self.internal(tables, tcx);
+        check_args(tcx, def_id, args)?;
+        Ok(args)
+    }
+}
+
+/// Check that the internal arguments match the number and kind of the generic parameters of
+/// `def_id`.
+pub(crate) fn check_args<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    def_id: rustc_span::def_id::DefId,
+    args: &[rustc_ty::GenericArg<'tcx>],
+) -> Result<(), Error> {
+    let generics = tcx.generics_of(def_id);
+    if args.len() != generics.count() {
+        return Err(Error::new(format!(
+            "Expected {} generic arguments for `{}`, but found {}",
+            generics.count(),
+            tcx.def_path_str(def_id),
+            args.len()
+        )));
+    }
+    for (idx, arg) in args.iter().enumerate() {
+        let param = generics.param_at(idx, tcx);
+        let (matches, descr) = match arg.unpack() {
+            rustc_ty::GenericArgKind::Lifetime(_) => {
+                (matches!(param.kind, rustc_ty::GenericParamDefKind::Lifetime), "lifetime")
+            }
+            rustc_ty::GenericArgKind::Type(_) => {
+                (matches!(param.kind, rustc_ty::GenericParamDefKind::Type { .. }), "type")
+            }
+            rustc_ty::GenericArgKind::Const(_) => {
+                (matches!(param.kind, rustc_ty::GenericParamDefKind::Const { .. }), "constant")
+            }
+        };
+        if !matches {
+            return Err(Error::new(format!(
+                "Expected a {} for parameter `{}` of `{}`, but found a {descr}",
+                param.kind.descr(),
+                param.name,
+                tcx.def_path_str(def_id),
+            )));
+        }
+    }
+    Ok(())
+}
+
+impl RustcInternalChecked<ClosureDef> for GenericArgs {
+    type T<'tcx> = rustc_ty::ClosureArgs<TyCtxt<'tcx>>;
+
+    /// Check that these arguments have the shape of closure arguments, i.e., the arguments of the
+    /// enclosing item followed by the closure kind, the signature and the tupled upvars.
+    fn internal_checked<'tcx>(
+        &self,
+        def: &ClosureDef,
+        tables: &mut Tables<'_>,
+        tcx: TyCtxt<'tcx>,
+    ) -> Result<Self::T<'tcx>, Error> {
+        let def_id = def.0.internal(tables, tcx);
+        let args = self.internal(tables, tcx);
+        let [kind_ty, sig_ty, upvars_ty] = synthetic_args(tcx, def_id, args)?;
+        check_synthetic_arg(tcx, def_id, "closure kind", kind_ty, |ty| {
+            matches!(
+                ty.kind(),
+                rustc_ty::Int(rustc_ty::IntTy::I8 | rustc_ty::IntTy::I16 | rustc_ty::IntTy::I32)
+            )
+        })?;
+        check_synthetic_arg(tcx, def_id, "signature", sig_ty, |ty| ty.is_fn_ptr())?;
+        check_synthetic_arg(tcx, def_id, "upvars", upvars_ty, |ty| {
+            matches!(ty.kind(), rustc_ty::Tuple(_))
+        })?;
+        Ok(rustc_ty::ClosureArgs { args })
+    }
+}
+
+impl RustcInternalChecked<CoroutineDef> for GenericArgs {
+    type T<'tcx> = rustc_ty::CoroutineArgs<TyCtxt<'tcx>>;
+
+    /// Check that these arguments have the shape of coroutine arguments, i.e., the arguments of
+    /// the enclosing item followed by the kind, the resume, yield and return types, the witness and
+    /// the tupled upvars.
+    fn internal_checked<'tcx>(
+        &self,
+        def: &CoroutineDef,
+        tables: &mut Tables<'_>,
+        tcx: TyCtxt<'tcx>,
+    ) -> Result<Self::T<'tcx>, Error> {
+        let def_id = def.0.internal(tables, tcx);
+        let args = self.internal(tables, tcx);
+        let [kind_ty, _resume_ty, _yield_ty, _return_ty, witness, upvars_ty] =
+            synthetic_args(tcx, def_id, args)?;
+        // Coroutines returned by coroutine-closures encode the closure kind, others use `()`.
+        check_synthetic_arg(tcx, def_id, "coroutine kind", kind_ty, |ty| {
+            ty.is_unit()
+                || matches!(
+                    ty.kind(),
+                    rustc_ty::Int(
+                        rustc_ty::IntTy::I8 | rustc_ty::IntTy::I16 | rustc_ty::IntTy::I32
+                    )
+                )
+        })?;
+        check_synthetic_arg(tcx, def_id, "witness", witness, |ty| {
+            matches!(ty.kind(), rustc_ty::CoroutineWitness(..))
+        })?;
+        check_synthetic_arg(tcx, def_id, "upvars", upvars_ty, |ty| {
+            matches!(ty.kind(), rustc_ty::Tuple(_))
+        })?;
+        Ok(rustc_ty::CoroutineArgs { args })
+    }
+}
+
+/// Split the arguments of a closure-like item into the arguments of its enclosing item and the
+/// `N` synthetic types that follow them.
+fn synthetic_args<'tcx, const N: usize>(
+    tcx: TyCtxt<'tcx>,
+    def_id: rustc_span::def_id::DefId,
+    args: rustc_ty::GenericArgsRef<'tcx>,
+) -> Result<[rustc_ty::GenericArg<'tcx>; N], Error> {
+    if !tcx.is_closure_like(def_id) {
+        return Err(Error::new(format!(
+            "Expected a closure-like item, but found `{}`",
+            tcx.def_path_str(def_id)
+        )));
+    }
+    let parent_count = tcx.generics_of(tcx.typeck_root_def_id(def_id)).count();
+    if args.len() != parent_count + N {
+        return Err(Error::new(format!(
+            "Expected {} generic arguments for `{}`, but found {}",
+            parent_count + N,
+            tcx.def_path_str(def_id),
+            args.len()
+        )));
+    }
+    Ok(args[parent_count..].try_into().unwrap())
+}
+
+/// Check that the synthetic argument `name` of a closure-like item is a type accepted by `check`.
+fn check_synthetic_arg<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    def_id: rustc_span::def_id::DefId,
+    name: &str,
+    arg: rustc_ty::GenericArg<'tcx>,
+    check: impl FnOnce(InternalTy<'tcx>) -> bool,
+) -> Result<(), Error> {
+    match arg.as_type() {
+        Some(ty) if check(ty) => Ok(()),
+        _ => Err(Error::new(format!(
+            "Unexpected {name} argument for `{}`: `{arg}`",
+            tcx.def_path_str(def_id)
+        ))),
+    }
+}
+
+/// Describe the kind of a generic argument the same way as `GenericParamDefKind::descr`.
+///
+/// Note that we cannot use the `Debug` implementation of the argument while the tables are
+/// borrowed.
+fn generic_arg_descr(arg: &GenericArgKind) -> &'static str {
+    match arg {
+        GenericArgKind::Lifetime(_) => "lifetime",
+        GenericArgKind::Type(_) => "type",
+        GenericArgKind::Const(_) => "constant",
+    }
+}
+
+impl RustcInternalChecked<DefId> for Generics {
+    type T<'tcx> = &'tcx rustc_ty::Generics;
+
+    /// Check that these generics declare the same parameters as the generics of `def`.
+    fn internal_checked<'tcx>(
+        &self,
+        def: &DefId,
+        tables: &mut Tables<'_>,
+        tcx: TyCtxt<'tcx>,
+    ) -> Result<Self::T<'tcx>, Error> {
+        let def_id = def.internal(tables, tcx);
+        let generics = tcx.generics_of(def_id);
+        if self.parent_count != generics.parent_count
+            || self.params.len() != generics.own_params.len()
+        {
+            return Err(Error::new(format!(
+                "Expected {} parent and {} own generic parameters for `{}`, but found {} and {}",
+                generics.parent_count,
+                generics.own_params.len(),
+                tcx.def_path_str(def_id),
+                self.parent_count,
+                self.params.len()
+            )));
+        }
+        for (param, expected) in self.params.iter().zip(&generics.own_params) {
+            if param.def_id.0.internal(tables, tcx) != expected.def_id {
+                return Err(Error::new(format!(
+                    "Expected parameter `{}` of `{}`, but found `{}`",
+                    expected.name,
+                    tcx.def_path_str(def_id),
+                    param.name
+                )));
+            }
+        }
+        Ok(generics)
+    }
+}
+
+impl RustcInternal for GenericParamDef {
+    type T<'tcx> = rustc_ty::GenericParamDef;
+
+    fn internal<'tcx>(&self, tables: &mut Tables<'_>, tcx: TyCtxt<'tcx>) -> Self::T<'tcx> {
+        let def_id = self.def_id.0.internal(tables, tcx);
+        // The `Self` parameter of a trait is identified by the trait itself.
+        let owner = match tcx.def_kind(def_id) {
+            DefKind::Trait | DefKind::TraitAlias => def_id,
+            _ => tcx.parent(def_id),
+        };
+        tcx.generics_of(owner).param_at(self.index as usize, tcx).clone()
+    }
+}
+
 impl RustcInternal for GenericArgKind {
     type T<'tcx> = rustc_ty::GenericArg<'tcx>;
     fn internal<'tcx>(&self, tables: &mut Tables<'_>, tcx: TyCtxt<'tcx>) -> Self::T<'tcx> {
@@ -62,18 +394,72 @@ fn internal<'tcx>(&self, tables: &mut Tables<'_>, tcx: TyCtxt<'tcx>) -> Self::T<
     }
 }
 
+impl RustcInternalChecked<ParamEnv> for GenericArgKind {
+    type T<'tcx> = rustc_ty::GenericArg<'tcx>;
+
+    /// Reveal the opaque types in a type argument using the given parameter environment, so they
+    /// don't leak into a context where their hidden type is expected.
+    ///
+    /// Revealing requires a parameter environment with `Reveal::All`, and it erases the regions
+    /// of the type argument. Arguments without opaque types are converted as is.
+    fn internal_checked<'tcx>(
+        &self,
+        param_env: &ParamEnv,
+        tables: &mut Tables<'_>,
+        tcx: TyCtxt<'tcx>,
+    ) -> Result<Self::T<'tcx>, Error> {
+        let arg = self.internal(tables, tcx);
+        let Some(ty) = arg.as_type() else { return Ok(arg) };
+        if !ty.has_opaque_types() {
+            return Ok(arg);
+        }
+        let param_env = param_env.internal(tables, tcx);
+        if param_env.reveal() != rustc_middle::traits::Reveal::All {
+            return Err(Error::new(format!(
+                "Cannot reveal the opaque types of `{ty}` in a user facing parameter environment"
+            )));
+        }
+        match tcx.try_normalize_erasing_regions(param_env, ty) {
+            Ok(revealed) if !revealed.has_opaque_types() => Ok(revealed.into()),
+            _ => Err(Error::new(format!("Failed to reveal the opaque types of `{ty}`"))),
+        }
+    }
+}
+
 impl RustcInternal for Region {
     type T<'tcx> = rustc_ty::Region<'tcx>;
-    fn internal<'tcx>(&self, _tables: &mut Tables<'_>, tcx: TyCtxt<'tcx>) -> Self::T<'tcx> {
-        // Cannot recover region. Use erased for now.
-        tcx.lifetimes.re_erased
+    fn internal<'tcx>(&self, tables: &mut Tables<'_>, tcx: TyCtxt<'tcx>) -> Self::T<'tcx> {
+        self.kind.internal(tables, tcx)
     }
 }
 
-impl RustcInternal for Ty {
-    type T<'tcx> = InternalTy<'tcx>;
+impl RustcInternal for RegionKind {
+    type T<'tcx> = rustc_ty::Region<'tcx>;
+
     fn internal<'tcx>(&self, tables: &mut Tables<'_>, tcx: TyCtxt<'tcx>) -> Self::T<'tcx> {
-        tcx.lift(tables.types[*self]).unwrap()
+        match self {
+            RegionKind::ReEarlyParam(param) => rustc_ty::Region::new_early_param(
+                tcx,
+                rustc_ty::EarlyParamRegion {
+                    index: param.index,
+                    name: param.name.internal(tables, tcx),
+                },
+            ),
+            RegionKind::ReBound(debruijn, bound) => rustc_ty::Region::new_bound(
+                tcx,
+                rustc_ty::DebruijnIndex::from_u32(*debruijn),
+                bound.internal(tables, tcx),
+            ),
+            RegionKind::ReStatic => tcx.lifetimes.re_static,
+            RegionKind::RePlaceholder(placeholder) => rustc_ty::Region::new_placeholder(
+                tcx,
+                rustc_ty::PlaceholderRegion {
+                    universe: rustc_ty::UniverseIndex::from_u32(placeholder.universe),
+                    bound: placeholder.bound.internal(tables, tcx),
+                },
+            ),
+            RegionKind::ReErased => tcx.lifetimes.re_erased,
+        }
     }
 }
 
@@ -84,6 +470,30 @@ fn internal<'tcx>(&self, tables: &mut Tables<'_>, tcx: TyCtxt<'tcx>) -> Self::T<
     }
 }
 
+impl RustcInternalChecked<()> for TyConstKind {
+    type T<'tcx> = InternalConst<'tcx>;
+
+    /// Build an unevaluated type system constant from its definition and generic arguments,
+    /// checking that the arguments match the definition.
+    ///
+    /// Other kinds of constants must be converted from their [TyConst].
+    fn internal_checked<'tcx>(
+        &self,
+        _context: &(),
+        tables: &mut Tables<'_>,
+        tcx: TyCtxt<'tcx>,
+    ) -> Result<Self::T<'tcx>, Error> {
+        let TyConstKind::Unevaluated(def, args) = self else {
+            return Err(Error::new(format!(
+                "Expected an unevaluated constant, but found `{self:?}`"
+            )));
+        };
+        let args = args.internal_checked(&def.0, tables, tcx)?;
+        let uv = rustc_ty::UnevaluatedConst::new(def.0.internal(tables, tcx), args);
+        Ok(InternalConst::new_unevaluated(tcx, uv))
+    }
+}
+
 impl RustcInternal for Pattern {
     type T<'tcx> = rustc_ty::Pattern<'tcx>;
     fn internal<'tcx>(&self, tables: &mut Tables<'_>, tcx: TyCtxt<'tcx>) -> Self::T<'tcx> {
@@ -97,6 +507,57 @@ fn internal<'tcx>(&self, tables: &mut Tables<'_>, tcx: TyCtxt<'tcx>) -> Self::T<
     }
 }
 
+impl RustcInternalChecked<Ty> for Pattern {
+    type T<'tcx> = rustc_ty::Pattern<'tcx>;
+
+    /// Check that the range endpoints have the type of the pattern, and that the range is not
+    /// empty when both endpoints can be evaluated.
+    ///
+    /// The type of an endpoint is checked even if it cannot be evaluated. Only generic parameters
+    /// are not checked, since their type is not known without the generics they belong to.
+    fn internal_checked<'tcx>(
+        &self,
+        ty: &Ty,
+        tables: &mut Tables<'_>,
+        tcx: TyCtxt<'tcx>,
+    ) -> Result<Self::T<'tcx>, Error> {
+        let ty = ty.internal(tables, tcx);
+        let pattern = self.internal(tables, tcx);
+        let rustc_ty::PatternKind::Range { start, end, include_end } = *pattern;
+        let param_env = rustc_ty::ParamEnv::reveal_all();
+        let eval = |bound: Option<InternalConst<'tcx>>| {
+            let Some(bound) = bound else {
+                return Ok(None);
+            };
+            let bound_ty = match bound.kind() {
+                rustc_ty::ConstKind::Value(bound_ty, _) => Some(bound_ty),
+                rustc_ty::ConstKind::Unevaluated(uv) => {
+                    Some(tcx.type_of(uv.def).instantiate(tcx, uv.args))
+                }
+                _ => None,
+            };
+            if let Some(bound_ty) = bound_ty.filter(|bound_ty| *bound_ty != ty) {
+                return Err(Error::new(format!(
+                    "Expected range endpoint `{bound}` to have type `{ty}`, but found `{bound_ty}`"
+                )));
+            }
+            Ok(bound.try_eval_scalar_int(tcx, param_env).map(|(_, value)| value))
+        };
+        if let (Some(start), Some(end)) = (eval(start)?, eval(end)?) {
+            let ordering = if ty.is_signed() {
+                start.to_int(start.size()).cmp(&end.to_int(end.size()))
+            } else {
+                start.to_uint(start.size()).cmp(&end.to_uint(end.size()))
+            };
+            if ordering.is_gt() || (ordering.is_eq() && !include_end) {
+                let range = if include_end { "..=" } else { ".." };
+                return Err(Error::new(format!("Range pattern `{start}{range}{end}` is empty")));
+            }
+        }
+        Ok(pattern)
+    }
+}
+
 impl RustcInternal for RigidTy {
     type T<'tcx> = rustc_ty::TyKind<'tcx>;
 
@@ -120,13 +581,13 @@ fn internal<'tcx>(&self, tables: &mut Tables<'_>, tcx: TyCtxt<'tcx>) -> Self::T<
             RigidTy::Str => rustc_ty::TyKind::Str,
             RigidTy::Slice(ty) => rustc_ty::TyKind::Slice(ty.internal(tables, tcx)),
             RigidTy::RawPtr(ty, mutability) => {
-                rustc_ty::TyKind::RawPtr(ty.internal(tables, tcx), mutability.internal(tables, tcx))
+                let rustc_ty::TypeAndMut { ty, mutbl } = (*ty, *mutability).internal(tables, tcx);
+                rustc_ty::TyKind::RawPtr(ty, mutbl)
+            }
+            RigidTy::Ref(region, ty, mutability) => {
+                let rustc_ty::TypeAndMut { ty, mutbl } = (*ty, *mutability).internal(tables, tcx);
+                rustc_ty::TyKind::Ref(region.internal(tables, tcx), ty, mutbl)
             }
-            RigidTy::Ref(region, ty, mutability) => rustc_ty::TyKind::Ref(
-                region.internal(tables, tcx),
-                ty.internal(tables, tcx),
-                mutability.internal(tables, tcx),
-            ),
             RigidTy::Foreign(def) => rustc_ty::TyKind::Foreign(def.0.internal(tables, tcx)),
             RigidTy::FnDef(def, args) => {
                 rustc_ty::TyKind::FnDef(def.0.internal(tables, tcx), args.internal(tables, tcx))
@@ -145,11 +606,17 @@ fn internal<'tcx>(&self, tables: &mut Tables<'_>, tcx: TyCtxt<'tcx>) -> Self::T<
                 def.0.internal(tables, tcx),
                 args.internal(tables, tcx),
             ),
-            RigidTy::Dynamic(predicate, region, dyn_kind) => rustc_ty::TyKind::Dynamic(
-                tcx.mk_poly_existential_predicates(&predicate.internal(tables, tcx)),
-                region.internal(tables, tcx),
-                dyn_kind.internal(tables, tcx),
-            ),
+            RigidTy::Dynamic(predicate, region, dyn_kind) => {
+                // The interner expects the predicates in their canonical order, so that
+                // `dyn Trait + Send + Sync` and `dyn Trait + Sync + Send` are the same type.
+                let mut predicates = predicate.internal(tables, tcx);
+                predicates.sort_by(|a, b| a.skip_binder().stable_cmp(tcx, &b.skip_binder()));
+                rustc_ty::TyKind::Dynamic(
+                    tcx.mk_poly_existential_predicates(&predicates),
+                    region.internal(tables, tcx),
+                    dyn_kind.internal(tables, tcx),
+                )
+            }
             RigidTy::Tuple(tys) => {
                 rustc_ty::TyKind::Tuple(tcx.mk_type_list(&tys.internal(tables, tcx)))
             }
@@ -157,10 +624,63 @@ fn internal<'tcx>(&self, tables: &mut Tables<'_>, tcx: TyCtxt<'tcx>) -> Self::T<
     }
 }
 
-impl RustcInternal for IntTy {
-    type T<'tcx> = rustc_ty::IntTy;
+impl RustcInternal for TyKind {
+    type T<'tcx> = InternalTy<'tcx>;
 
-    fn internal<'tcx>(&self, _tables: &mut Tables<'_>, _tcx: TyCtxt<'tcx>) -> Self::T<'tcx> {
+    /// Build the type of this kind. Note that the De Bruijn index of a bound type is relative to
+    /// the binders that enclose the type, where `0` refers to the innermost binder.
+    fn internal<'tcx>(&self, tables: &mut Tables<'_>, tcx: TyCtxt<'tcx>) -> Self::T<'tcx> {
+        match self {
+            TyKind::RigidTy(rigid) => tcx.mk_ty_from_kind(rigid.internal(tables, tcx)),
+            TyKind::Alias(kind, alias_ty) => InternalTy::new_alias(
+                tcx,
+                kind.internal(tables, tcx),
+                alias_ty.internal(tables, tcx),
+            ),
+            TyKind::Param(param) => {
+                InternalTy::new_param(tcx, param.index, param.name.internal(tables, tcx))
+            }
+            TyKind::Bound(debruijn, bound_ty) => InternalTy::new_bound(
+                tcx,
+                rustc_ty::DebruijnIndex::from_usize(*debruijn),
+                bound_ty.internal(tables, tcx),
+            ),
+        }
+    }
+}
+
+impl RustcInternalChecked<()> for RigidTy {
+    type T<'tcx> = rustc_ty::TyKind<'tcx>;
+
+    /// Check that the principal trait of a `dyn*` type is object safe.
+    ///
+    /// Note that `dyn*` types themselves are always pointer-sized. The size of the values that are
+    /// coerced into them is checked during coercion instead.
+    fn internal_checked<'tcx>(
+        &self,
+        _context: &(),
+        tables: &mut Tables<'_>,
+        tcx: TyCtxt<'tcx>,
+    ) -> Result<Self::T<'tcx>, Error> {
+        let kind = self.internal(tables, tcx);
+        if let rustc_ty::TyKind::Dynamic(predicates, _, rustc_ty::DynKind::DynStar) = kind {
+            if let Some(principal) = predicates.principal_def_id() {
+                if !tcx.is_object_safe(principal) {
+                    return Err(Error::new(format!(
+                        "Expected an object safe trait for a `dyn*` type, but `{}` is not",
+                        tcx.def_path_str(principal)
+                    )));
+                }
+            }
+        }
+        Ok(kind)
+    }
+}
+
+impl IntoInternal for IntTy {
+    type T = rustc_ty::IntTy;
+
+    fn to_internal(&self) -> Self::T {
         match self {
             IntTy::Isize => rustc_ty::IntTy::Isize,
             IntTy::I8 => rustc_ty::IntTy::I8,
@@ -172,10 +692,10 @@ fn internal<'tcx>(&self, _tables: &mut Tables<'_>, _tcx: TyCtxt<'tcx>) -> Self::
     }
 }
 
-impl RustcInternal for UintTy {
-    type T<'tcx> = rustc_ty::UintTy;
+impl IntoInternal for UintTy {
+    type T = rustc_ty::UintTy;
 
-    fn internal<'tcx>(&self, _tables: &mut Tables<'_>, _tcx: TyCtxt<'tcx>) -> Self::T<'tcx> {
+    fn to_internal(&self) -> Self::T {
         match self {
             UintTy::Usize => rustc_ty::UintTy::Usize,
             UintTy::U8 => rustc_ty::UintTy::U8,
@@ -187,10 +707,10 @@ fn internal<'tcx>(&self, _tables: &mut Tables<'_>, _tcx: TyCtxt<'tcx>) -> Self::
     }
 }
 
-impl RustcInternal for FloatTy {
-    type T<'tcx> = rustc_ty::FloatTy;
+impl IntoInternal for FloatTy {
+    type T = rustc_ty::FloatTy;
 
-    fn internal<'tcx>(&self, _tables: &mut Tables<'_>, _tcx: TyCtxt<'tcx>) -> Self::T<'tcx> {
+    fn to_internal(&self) -> Self::T {
         match self {
             FloatTy::F16 => rustc_ty::FloatTy::F16,
             FloatTy::F32 => rustc_ty::FloatTy::F32,
@@ -200,10 +720,10 @@ fn internal<'tcx>(&self, _tables: &mut Tables<'_>, _tcx: TyCtxt<'tcx>) -> Self::
     }
 }
 
-impl RustcInternal for Mutability {
-    type T<'tcx> = rustc_ty::Mutability;
+impl IntoInternal for Mutability {
+    type T = rustc_ty::Mutability;
 
-    fn internal<'tcx>(&self, _tables: &mut Tables<'_>, _tcx: TyCtxt<'tcx>) -> Self::T<'tcx> {
+    fn to_internal(&self) -> Self::T {
         match self {
             Mutability::Not => rustc_ty::Mutability::Not,
             Mutability::Mut => rustc_ty::Mutability::Mut,
@@ -211,10 +731,22 @@ fn internal<'tcx>(&self, _tables: &mut Tables<'_>, _tcx: TyCtxt<'tcx>) -> Self::
     }
 }
 
-impl RustcInternal for Movability {
-    type T<'tcx> = rustc_ty::Movability;
+/// The pointee type and mutability of a reference or a raw pointer.
+impl RustcInternal for (Ty, Mutability) {
+    type T<'tcx> = rustc_ty::TypeAndMut<'tcx>;
 
-    fn internal<'tcx>(&self, _tables: &mut Tables<'_>, _tcx: TyCtxt<'tcx>) -> Self::T<'tcx> {
+    fn internal<'tcx>(&self, tables: &mut Tables<'_>, tcx: TyCtxt<'tcx>) -> Self::T<'tcx> {
+        rustc_ty::TypeAndMut {
+            ty: self.0.internal(tables, tcx),
+            mutbl: self.1.internal(tables, tcx),
+        }
+    }
+}
+
+impl IntoInternal for Movability {
+    type T = rustc_ty::Movability;
+
+    fn to_internal(&self) -> Self::T {
         match self {
             Movability::Static => rustc_ty::Movability::Static,
             Movability::Movable => rustc_ty::Movability::Movable,
@@ -222,6 +754,47 @@ fn internal<'tcx>(&self, _tables: &mut Tables<'_>, _tcx: TyCtxt<'tcx>) -> Self::
     }
 }
 
+impl IntoInternal for CoroutineKind {
+    type T = rustc_hir::CoroutineKind;
+
+    fn to_internal(&self) -> Self::T {
+        match self {
+            CoroutineKind::Desugared(desugaring, source) => {
+                rustc_hir::CoroutineKind::Desugared(desugaring.to_internal(), source.to_internal())
+            }
+            CoroutineKind::Coroutine(movability) => {
+                rustc_hir::CoroutineKind::Coroutine(movability.to_internal())
+            }
+        }
+    }
+}
+
+impl IntoInternal for CoroutineDesugaring {
+    type T = rustc_hir::CoroutineDesugaring;
+
+    fn to_internal(&self) -> Self::T {
+        match self {
+            CoroutineDesugaring::Async => rustc_hir::CoroutineDesugaring::Async,
+            CoroutineDesugaring::Gen => rustc_hir::CoroutineDesugaring::Gen,
+            CoroutineDesugaring::AsyncGen => rustc_hir::CoroutineDesugaring::AsyncGen,
+        }
+    }
+}
+
+impl IntoInternal for CoroutineSource {
+    type T = rustc_hir::CoroutineSource;
+
+    /// Note that the compiler does not distinguish the body of an async closure from the body of
+    /// any other `async` or `gen` closure, so both are represented by `Closure`.
+    fn to_internal(&self) -> Self::T {
+        match self {
+            CoroutineSource::Block => rustc_hir::CoroutineSource::Block,
+            CoroutineSource::Closure => rustc_hir::CoroutineSource::Closure,
+            CoroutineSource::Fn => rustc_hir::CoroutineSource::Fn,
+        }
+    }
+}
+
 impl RustcInternal for FnSig {
     type T<'tcx> = rustc_ty::FnSig<'tcx>;
 
@@ -236,10 +809,43 @@ fn internal<'tcx>(&self, tables: &mut Tables<'_>, tcx: TyCtxt<'tcx>) -> Self::T<
     }
 }
 
-impl RustcInternal for VariantIdx {
-    type T<'tcx> = rustc_target::abi::VariantIdx;
+impl RustcInternalChecked<()> for FnSig {
+    type T<'tcx> = rustc_ty::FnSig<'tcx>;
+
+    /// Check that the signature has an output type, and that a C-variadic signature has at least
+    /// one fixed argument and an ABI that supports variadic arguments.
+    ///
+    /// Note that every ABI can be combined with either safety. For instance, whether an intrinsic
+    /// is safe depends on the intrinsic itself, which is not part of the signature.
+    fn internal_checked<'tcx>(
+        &self,
+        _context: &(),
+        tables: &mut Tables<'_>,
+        tcx: TyCtxt<'tcx>,
+    ) -> Result<Self::T<'tcx>, Error> {
+        let Some(inputs) = self.inputs_and_output.len().checked_sub(1) else {
+            return Err(Error::new("Expected the signature to have an output type".to_string()));
+        };
+        if self.c_variadic && inputs == 0 {
+            return Err(Error::new(
+                "Expected a C-variadic signature to have at least one fixed argument".to_string(),
+            ));
+        }
+        let sig = self.internal(tables, tcx);
+        if sig.c_variadic && !sig.abi.supports_varargs() {
+            return Err(Error::new(format!(
+                "The `{}` ABI does not support C-variadic signatures",
+                sig.abi.name()
+            )));
+        }
+        Ok(sig)
+    }
+}
+
+impl IntoInternal for VariantIdx {
+    type T = rustc_target::abi::VariantIdx;
 
-    fn internal<'tcx>(&self, _tables: &mut Tables<'_>, _tcx: TyCtxt<'tcx>) -> Self::T<'tcx> {
+    fn to_internal(&self) -> Self::T {
         rustc_target::abi::VariantIdx::from(self.to_index())
     }
 }
@@ -273,6 +879,34 @@ fn internal<'tcx>(&self, tables: &mut Tables<'_>, tcx: TyCtxt<'tcx>) -> Self::T<
     }
 }
 
+impl RustcInternalChecked<()> for MirConst {
+    type T<'tcx> = rustc_middle::mir::Const<'tcx>;
+
+    /// Build a type system constant from its parts, checking that the type of its value matches
+    /// the declared type. Other kinds of constants are converted as is.
+    fn internal_checked<'tcx>(
+        &self,
+        _context: &(),
+        tables: &mut Tables<'_>,
+        tcx: TyCtxt<'tcx>,
+    ) -> Result<Self::T<'tcx>, Error> {
+        let ConstantKind::Ty(ty_const) = self.kind() else {
+            return Ok(self.internal(tables, tcx));
+        };
+        let ty = self.ty().internal(tables, tcx);
+        let ct = ty_const.internal(tables, tcx);
+        // Only values carry their type. Other constants are checked when they are evaluated.
+        if let rustc_ty::ConstKind::Value(value_ty, _) = ct.kind() {
+            if value_ty != ty {
+                return Err(Error::new(format!(
+                    "Expected a constant of type `{ty}`, but found `{ct}` of type `{value_ty}`"
+                )));
+            }
+        }
+        Ok(rustc_middle::mir::Const::Ty(ty, ct))
+    }
+}
+
 impl RustcInternal for MonoItem {
     type T<'tcx> = rustc_middle::mir::mono::MonoItem<'tcx>;
 
@@ -296,6 +930,69 @@ fn internal<'tcx>(&self, tables: &mut Tables<'_>, tcx: TyCtxt<'tcx>) -> Self::T<
     }
 }
 
+impl RustcInternalChecked<(DefId, GenericArgs)> for InstanceKind {
+    type T<'tcx> = rustc_ty::InstanceKind<'tcx>;
+
+    /// Build the instance kind of the given definition and arguments.
+    ///
+    /// The stable kind does not distinguish between the different compiler generated shims, so
+    /// shims are resolved from the definition and its arguments the same way the compiler does.
+    fn internal_checked<'tcx>(
+        &self,
+        (def_id, args): &(DefId, GenericArgs),
+        tables: &mut Tables<'_>,
+        tcx: TyCtxt<'tcx>,
+    ) -> Result<Self::T<'tcx>, Error> {
+        let def_id = def_id.internal(tables, tcx);
+        match self {
+            InstanceKind::Item => Ok(rustc_ty::InstanceKind::Item(def_id)),
+            InstanceKind::Intrinsic => {
+                if tcx.intrinsic(def_id).is_none() {
+                    return Err(Error::new(format!(
+                        "Expected an intrinsic, but found `{}`",
+                        tcx.def_path_str(def_id)
+                    )));
+                }
+                Ok(rustc_ty::InstanceKind::Intrinsic(def_id))
+            }
+            InstanceKind::Virtual { idx } => {
+                if tcx.trait_of_item(def_id).is_none() {
+                    return Err(Error::new(format!(
+                        "Expected a trait method for a virtual call, but found `{}`",
+                        tcx.def_path_str(def_id)
+                    )));
+                }
+                Ok(rustc_ty::InstanceKind::Virtual(def_id, *idx))
+            }
+            InstanceKind::Shim => {
+                let args = args.internal(tables, tcx);
+                let instance = rustc_ty::Instance::try_resolve(
+                    tcx,
+                    rustc_ty::ParamEnv::reveal_all(),
+                    def_id,
+                    args,
+                );
+                match instance {
+                    Ok(Some(instance))
+                        if !matches!(
+                            instance.def,
+                            rustc_ty::InstanceKind::Item(..)
+                                | rustc_ty::InstanceKind::Intrinsic(..)
+                                | rustc_ty::InstanceKind::Virtual(..)
+                        ) =>
+                    {
+                        Ok(instance.def)
+                    }
+                    _ => Err(Error::new(format!(
+                        "`{}` does not resolve to a shim",
+                        tcx.def_path_str_with_args(def_id, args)
+                    ))),
+                }
+            }
+        }
+    }
+}
+
 impl RustcInternal for StaticDef {
     type T<'tcx> = rustc_span::def_id::DefId;
 
@@ -322,35 +1019,211 @@ fn internal<'tcx>(&self, tables: &mut Tables<'_>, tcx: TyCtxt<'tcx>) -> Self::T<
     }
 }
 
+#[allow(rustc::usage_of_qualified_ty)]
+impl<T> RustcInternalChecked<()> for Binder<T>
+where
+    T: RustcInternal,
+    for<'tcx> T::T<'tcx>: rustc_ty::TypeVisitable<rustc_ty::TyCtxt<'tcx>>,
+{
+    type T<'tcx> = rustc_ty::Binder<'tcx, T::T<'tcx>>;
+
+    /// Check that every variable bound by this binder is declared in its `bound_vars`.
+    fn internal_checked<'tcx>(
+        &self,
+        _context: &(),
+        tables: &mut Tables<'_>,
+        tcx: TyCtxt<'tcx>,
+    ) -> Result<Self::T<'tcx>, Error> {
+        let value = self.value.internal(tables, tcx);
+        let mut validator = BoundVarValidator {
+            binder_index: rustc_ty::INNERMOST,
+            num_bound_vars: self.bound_vars.len(),
+        };
+        if let ControlFlow::Break(var) = value.visit_with(&mut validator) {
+            return Err(Error::new(format!(
+                "Bound variable `{var}` is out of range. The binder only declares {} variables",
+                self.bound_vars.len()
+            )));
+        }
+        Ok(rustc_ty::Binder::bind_with_vars(
+            value,
+            tcx.mk_bound_variable_kinds_from_iter(
+                self.bound_vars.iter().map(|bound| bound.internal(tables, tcx)),
+            ),
+        ))
+    }
+}
+
+/// Visitor that finds variables bound by the outermost binder whose index is out of range.
+struct BoundVarValidator {
+    binder_index: rustc_ty::DebruijnIndex,
+    num_bound_vars: usize,
+}
+
+impl BoundVarValidator {
+    fn check_var(
+        &self,
+        debruijn: rustc_ty::DebruijnIndex,
+        var: rustc_ty::BoundVar,
+    ) -> ControlFlow<usize> {
+        if debruijn == self.binder_index && var.as_usize() >= self.num_bound_vars {
+            ControlFlow::Break(var.as_usize())
+        } else {
+            ControlFlow::Continue(())
+        }
+    }
+}
+
+impl<'tcx> rustc_ty::TypeVisitor<TyCtxt<'tcx>> for BoundVarValidator {
+    type Result = ControlFlow<usize>;
+
+    fn visit_binder<T: TypeVisitable<TyCtxt<'tcx>>>(
+        &mut self,
+        t: &rustc_ty::Binder<'tcx, T>,
+    ) -> Self::Result {
+        self.binder_index.shift_in(1);
+        let result = t.super_visit_with(self);
+        self.binder_index.shift_out(1);
+        result
+    }
+
+    fn visit_ty(&mut self, ty: InternalTy<'tcx>) -> Self::Result {
+        // Nothing in this type refers to the binder being validated.
+        if ty.outer_exclusive_binder() <= self.binder_index {
+            return ControlFlow::Continue(());
+        }
+        if let rustc_ty::Bound(debruijn, bound_ty) = *ty.kind() {
+            self.check_var(debruijn, bound_ty.var)?;
+        }
+        ty.super_visit_with(self)
+    }
+
+    fn visit_region(&mut self, region: rustc_ty::Region<'tcx>) -> Self::Result {
+        if let rustc_ty::ReBound(debruijn, bound_region) = *region {
+            self.check_var(debruijn, bound_region.var)?;
+        }
+        ControlFlow::Continue(())
+    }
+
+    fn visit_const(&mut self, ct: InternalConst<'tcx>) -> Self::Result {
+        if let rustc_ty::ConstKind::Bound(debruijn, var) = ct.kind() {
+            self.check_var(debruijn, var)?;
+        }
+        ct.super_visit_with(self)
+    }
+}
+
 impl RustcInternal for BoundVariableKind {
     type T<'tcx> = rustc_ty::BoundVariableKind;
 
     fn internal<'tcx>(&self, tables: &mut Tables<'_>, tcx: TyCtxt<'tcx>) -> Self::T<'tcx> {
         match self {
-            BoundVariableKind::Ty(kind) => rustc_ty::BoundVariableKind::Ty(match kind {
-                BoundTyKind::Anon => rustc_ty::BoundTyKind::Anon,
-                BoundTyKind::Param(def, symbol) => rustc_ty::BoundTyKind::Param(
-                    def.0.internal(tables, tcx),
-                    Symbol::intern(symbol),
-                ),
-            }),
-            BoundVariableKind::Region(kind) => rustc_ty::BoundVariableKind::Region(match kind {
-                BoundRegionKind::BrAnon => rustc_ty::BoundRegionKind::BrAnon,
-                BoundRegionKind::BrNamed(def, symbol) => rustc_ty::BoundRegionKind::BrNamed(
-                    def.0.internal(tables, tcx),
-                    Symbol::intern(symbol),
-                ),
-                BoundRegionKind::BrEnv => rustc_ty::BoundRegionKind::BrEnv,
-            }),
+            BoundVariableKind::Ty(kind) => {
+                rustc_ty::BoundVariableKind::Ty(kind.internal(tables, tcx))
+            }
+            BoundVariableKind::Region(kind) => {
+                rustc_ty::BoundVariableKind::Region(kind.internal(tables, tcx))
+            }
             BoundVariableKind::Const => rustc_ty::BoundVariableKind::Const,
         }
     }
 }
 
-impl RustcInternal for DynKind {
-    type T<'tcx> = rustc_ty::DynKind;
+impl RustcInternalChecked<DefId> for BoundVariableKind {
+    type T<'tcx> = rustc_ty::BoundVariableKind;
+
+    /// Check that a bound type parameter is a type parameter declared by a binder of the given
+    /// item, and that its name matches the name of its definition.
+    fn internal_checked<'tcx>(
+        &self,
+        item: &DefId,
+        tables: &mut Tables<'_>,
+        tcx: TyCtxt<'tcx>,
+    ) -> Result<Self::T<'tcx>, Error> {
+        let BoundVariableKind::Ty(BoundTyKind::Param(def, symbol)) = self else {
+            return Ok(self.internal(tables, tcx));
+        };
+        let def_id = def.0.internal(tables, tcx);
+        let item_id = item.internal(tables, tcx);
+        if tcx.def_kind(def_id) != DefKind::TyParam {
+            return Err(Error::new(format!(
+                "Expected a type parameter, but `{}` is a {}",
+                tcx.def_path_str(def_id),
+                tcx.def_descr(def_id)
+            )));
+        }
+        if tcx.opt_parent(def_id) != Some(item_id) {
+            return Err(Error::new(format!(
+                "Type parameter `{}` is not declared by `{}`",
+                tcx.def_path_str(def_id),
+                tcx.def_path_str(item_id)
+            )));
+        }
+        let name = tcx.item_name(def_id);
+        if name.as_str() != symbol {
+            return Err(Error::new(format!(
+                "Expected type parameter `{name}`, but found `{symbol}`"
+            )));
+        }
+        Ok(self.internal(tables, tcx))
+    }
+}
 
-    fn internal<'tcx>(&self, _tables: &mut Tables<'_>, _tcx: TyCtxt<'tcx>) -> Self::T<'tcx> {
+impl RustcInternal for BoundTy {
+    type T<'tcx> = rustc_ty::BoundTy;
+
+    fn internal<'tcx>(&self, tables: &mut Tables<'_>, tcx: TyCtxt<'tcx>) -> Self::T<'tcx> {
+        rustc_ty::BoundTy {
+            var: rustc_ty::BoundVar::from_usize(self.var),
+            kind: self.kind.internal(tables, tcx),
+        }
+    }
+}
+
+impl RustcInternal for BoundTyKind {
+    type T<'tcx> = rustc_ty::BoundTyKind;
+
+    fn internal<'tcx>(&self, tables: &mut Tables<'_>, tcx: TyCtxt<'tcx>) -> Self::T<'tcx> {
+        match self {
+            BoundTyKind::Anon => rustc_ty::BoundTyKind::Anon,
+            BoundTyKind::Param(def, symbol) => rustc_ty::BoundTyKind::Param(
+                def.0.internal(tables, tcx),
+                symbol.internal(tables, tcx),
+            ),
+        }
+    }
+}
+
+impl RustcInternal for BoundRegion {
+    type T<'tcx> = rustc_ty::BoundRegion;
+
+    fn internal<'tcx>(&self, tables: &mut Tables<'_>, tcx: TyCtxt<'tcx>) -> Self::T<'tcx> {
+        rustc_ty::BoundRegion {
+            var: rustc_ty::BoundVar::from_u32(self.var),
+            kind: self.kind.internal(tables, tcx),
+        }
+    }
+}
+
+impl RustcInternal for BoundRegionKind {
+    type T<'tcx> = rustc_ty::BoundRegionKind;
+
+    fn internal<'tcx>(&self, tables: &mut Tables<'_>, tcx: TyCtxt<'tcx>) -> Self::T<'tcx> {
+        match self {
+            BoundRegionKind::BrAnon => rustc_ty::BoundRegionKind::BrAnon,
+            BoundRegionKind::BrNamed(def, symbol) => rustc_ty::BoundRegionKind::BrNamed(
+                def.0.internal(tables, tcx),
+                symbol.internal(tables, tcx),
+            ),
+            BoundRegionKind::BrEnv => rustc_ty::BoundRegionKind::BrEnv,
+        }
+    }
+}
+
+impl IntoInternal for DynKind {
+    type T = rustc_ty::DynKind;
+
+    fn to_internal(&self) -> Self::T {
         match self {
             DynKind::Dyn => rustc_ty::DynKind::Dyn,
             DynKind::DynStar => rustc_ty::DynKind::DynStar,
@@ -388,7 +1261,44 @@ fn internal<'tcx>(&self, tables: &mut Tables<'_>, tcx: TyCtxt<'tcx>) -> Self::T<
     }
 }
 
-impl RustcInternal for TermKind {
+impl RustcInternalChecked<()> for ExistentialProjection {
+    type T<'tcx> = rustc_ty::ExistentialProjection<'tcx>;
+
+    /// Check that the term kind matches the kind of the projected associated item.
+    fn internal_checked<'tcx>(
+        &self,
+        _context: &(),
+        tables: &mut Tables<'_>,
+        tcx: TyCtxt<'tcx>,
+    ) -> Result<Self::T<'tcx>, Error> {
+        let def_id = self.def_id.0.internal(tables, tcx);
+        let expected = match tcx.def_kind(def_id) {
+            DefKind::AssocTy => "type",
+            DefKind::AssocConst => "constant",
+            _ => {
+                return Err(Error::new(format!(
+                    "Expected an associated type or constant, but `{}` is a {}",
+                    tcx.def_path_str(def_id),
+                    tcx.def_descr(def_id)
+                )));
+            }
+        };
+        let found = match self.term {
+            TermKind::Type(_) => "type",
+            TermKind::Const(_) => "constant",
+        };
+        if expected != found {
+            return Err(Error::new(format!(
+                "Expected a {expected} term for {} `{}`, but found a {found}",
+                tcx.def_descr(def_id),
+                tcx.def_path_str(def_id)
+            )));
+        }
+        Ok(self.internal(tables, tcx))
+    }
+}
+
+impl RustcInternal for TermKind {
     type T<'tcx> = rustc_ty::Term<'tcx>;
 
     fn internal<'tcx>(&self, tables: &mut Tables<'_>, tcx: TyCtxt<'tcx>) -> Self::T<'tcx> {
@@ -399,6 +1309,192 @@ fn internal<'tcx>(&self, tables: &mut Tables<'_>, tcx: TyCtxt<'tcx>) -> Self::T<
     }
 }
 
+impl RustcInternal for ParamEnv {
+    type T<'tcx> = rustc_ty::ParamEnv<'tcx>;
+
+    fn internal<'tcx>(&self, tables: &mut Tables<'_>, tcx: TyCtxt<'tcx>) -> Self::T<'tcx> {
+        let caller_bounds = tcx.mk_clauses_from_iter(self.caller_bounds.iter().map(|clause| {
+            let clause: rustc_ty::Clause<'tcx> = clause.internal(tables, tcx).upcast(tcx);
+            clause
+        }));
+        rustc_ty::ParamEnv::new(caller_bounds, self.reveal.internal(tables, tcx))
+    }
+}
+
+impl IntoInternal for Reveal {
+    type T = rustc_middle::traits::Reveal;
+
+    fn to_internal(&self) -> Self::T {
+        match self {
+            Reveal::UserFacing => rustc_middle::traits::Reveal::UserFacing,
+            Reveal::All => rustc_middle::traits::Reveal::All,
+        }
+    }
+}
+
+impl RustcInternal for ClauseKind {
+    type T<'tcx> = rustc_ty::ClauseKind<'tcx>;
+
+    fn internal<'tcx>(&self, tables: &mut Tables<'_>, tcx: TyCtxt<'tcx>) -> Self::T<'tcx> {
+        match self {
+            ClauseKind::Trait(pred) => rustc_ty::ClauseKind::Trait(pred.internal(tables, tcx)),
+            ClauseKind::RegionOutlives(pred) => {
+                rustc_ty::ClauseKind::RegionOutlives(pred.internal(tables, tcx))
+            }
+            ClauseKind::TypeOutlives(pred) => {
+                rustc_ty::ClauseKind::TypeOutlives(pred.internal(tables, tcx))
+            }
+            ClauseKind::Projection(pred) => {
+                rustc_ty::ClauseKind::Projection(pred.internal(tables, tcx))
+            }
+            ClauseKind::ConstArgHasType(cnst, ty) => rustc_ty::ClauseKind::ConstArgHasType(
+                cnst.internal(tables, tcx),
+                ty.internal(tables, tcx),
+            ),
+            ClauseKind::WellFormed(arg) => {
+                rustc_ty::ClauseKind::WellFormed(arg.internal(tables, tcx))
+            }
+            ClauseKind::ConstEvaluatable(cnst) => {
+                rustc_ty::ClauseKind::ConstEvaluatable(cnst.internal(tables, tcx))
+            }
+        }
+    }
+}
+
+impl RustcInternal for TraitPredicate {
+    type T<'tcx> = rustc_ty::TraitPredicate<'tcx>;
+
+    fn internal<'tcx>(&self, tables: &mut Tables<'_>, tcx: TyCtxt<'tcx>) -> Self::T<'tcx> {
+        rustc_ty::TraitPredicate {
+            trait_ref: self.trait_ref.internal(tables, tcx),
+            polarity: self.polarity.internal(tables, tcx),
+        }
+    }
+}
+
+impl IntoInternal for PredicatePolarity {
+    type T = rustc_ty::PredicatePolarity;
+
+    fn to_internal(&self) -> Self::T {
+        match self {
+            PredicatePolarity::Positive => rustc_ty::PredicatePolarity::Positive,
+            PredicatePolarity::Negative => rustc_ty::PredicatePolarity::Negative,
+        }
+    }
+}
+
+impl IntoInternal for ImplPolarity {
+    type T = rustc_ty::ImplPolarity;
+
+    fn to_internal(&self) -> Self::T {
+        match self {
+            ImplPolarity::Positive => rustc_ty::ImplPolarity::Positive,
+            ImplPolarity::Negative => rustc_ty::ImplPolarity::Negative,
+            ImplPolarity::Reservation => rustc_ty::ImplPolarity::Reservation,
+        }
+    }
+}
+
+impl<T> RustcInternal for OutlivesPredicate<T, Region>
+where
+    T: RustcInternal,
+{
+    type T<'tcx> = rustc_ty::OutlivesPredicate<'tcx, T::T<'tcx>>;
+
+    fn internal<'tcx>(&self, tables: &mut Tables<'_>, tcx: TyCtxt<'tcx>) -> Self::T<'tcx> {
+        rustc_ty::OutlivesPredicate(self.0.internal(tables, tcx), self.1.internal(tables, tcx))
+    }
+}
+
+impl RustcInternal for ProjectionPredicate {
+    type T<'tcx> = rustc_ty::ProjectionPredicate<'tcx>;
+
+    fn internal<'tcx>(&self, tables: &mut Tables<'_>, tcx: TyCtxt<'tcx>) -> Self::T<'tcx> {
+        rustc_ty::ProjectionPredicate {
+            projection_term: self.projection_term.internal(tables, tcx),
+            term: self.term.internal(tables, tcx),
+        }
+    }
+}
+
+impl RustcInternal for AliasTerm {
+    type T<'tcx> = rustc_ty::AliasTerm<'tcx>;
+
+    fn internal<'tcx>(&self, tables: &mut Tables<'_>, tcx: TyCtxt<'tcx>) -> Self::T<'tcx> {
+        rustc_ty::AliasTerm::new_from_args(
+            tcx,
+            self.def_id.0.internal(tables, tcx),
+            self.args.internal(tables, tcx),
+        )
+    }
+}
+
+impl RustcInternal for AliasTy {
+    type T<'tcx> = rustc_ty::AliasTy<'tcx>;
+
+    fn internal<'tcx>(&self, tables: &mut Tables<'_>, tcx: TyCtxt<'tcx>) -> Self::T<'tcx> {
+        rustc_ty::AliasTy::new_from_args(
+            tcx,
+            self.def_id.0.internal(tables, tcx),
+            self.args.internal(tables, tcx),
+        )
+    }
+}
+
+impl RustcInternalChecked<AliasKind> for AliasTy {
+    type T<'tcx> = rustc_ty::AliasTy<'tcx>;
+
+    /// Check that the alias definition has the given kind. Note that the return position
+    /// `impl Trait` of a trait method is a projection of its synthesized associated type.
+    fn internal_checked<'tcx>(
+        &self,
+        kind: &AliasKind,
+        tables: &mut Tables<'_>,
+        tcx: TyCtxt<'tcx>,
+    ) -> Result<Self::T<'tcx>, Error> {
+        let def_id = self.def_id.0.internal(tables, tcx);
+        let actual = match tcx.def_kind(def_id) {
+            DefKind::AssocTy => {
+                if let DefKind::Impl { of_trait: false } = tcx.def_kind(tcx.parent(def_id)) {
+                    rustc_ty::Inherent
+                } else {
+                    rustc_ty::Projection
+                }
+            }
+            DefKind::OpaqueTy => rustc_ty::Opaque,
+            DefKind::TyAlias => rustc_ty::Weak,
+            def_kind => {
+                return Err(Error::new(format!(
+                    "Expected an alias, but found {} `{}`",
+                    def_kind.descr(def_id),
+                    tcx.def_path_str(def_id)
+                )));
+            }
+        };
+        let expected = kind.internal(tables, tcx);
+        if actual != expected {
+            return Err(Error::new(format!(
+                "Expected `{}` to be an alias of kind `{expected:?}`, but found `{actual:?}`",
+                tcx.def_path_str(def_id),
+            )));
+        }
+        Ok(self.internal(tables, tcx))
+    }
+}
+
+impl IntoInternal for AliasKind {
+    type T = rustc_ty::AliasTyKind;
+
+    fn to_internal(&self) -> Self::T {
+        match self {
+            AliasKind::Projection => rustc_ty::Projection,
+            AliasKind::Inherent => rustc_ty::Inherent,
+            AliasKind::Opaque => rustc_ty::Opaque,
+            AliasKind::Weak => rustc_ty::Weak,
+        }
+    }
+}
+
 impl RustcInternal for ExistentialTraitRef {
     type T<'tcx> = rustc_ty::ExistentialTraitRef<'tcx>;
 
@@ -410,6 +1506,62 @@ fn internal<'tcx>(&self, tables: &mut Tables<'_>, tcx: TyCtxt<'tcx>) -> Self::T<
     }
 }
 
+impl RustcInternalChecked<()> for ExistentialTraitRef {
+    type T<'tcx> = rustc_ty::ExistentialTraitRef<'tcx>;
+
+    /// Check that the arguments match the generic parameters of the trait, except for `Self`,
+    /// which is erased in an existential trait reference.
+    fn internal_checked<'tcx>(
+        &self,
+        _context: &(),
+        tables: &mut Tables<'_>,
+        tcx: TyCtxt<'tcx>,
+    ) -> Result<Self::T<'tcx>, Error> {
+        let def_id = self.def_id.0.internal(tables, tcx);
+        if !matches!(tcx.def_kind(def_id), DefKind::Trait | DefKind::TraitAlias) {
+            return Err(Error::new(format!(
+                "Expected a trait, but `{}` is a {}",
+                tcx.def_path_str(def_id),
+                tcx.def_descr(def_id)
+            )));
+        }
+        let generics = tcx.generics_of(def_id);
+        let expected = generics.count() - 1;
+        if self.generic_args.0.len() != expected {
+            let hint = if self.generic_args.0.len() == generics.count() {
+                ". Note that existential trait references do not include `Self`"
+            } else {
+                ""
+            };
+            return Err(Error::new(format!(
+                "Expected {expected} generic arguments for `{}`, but found {}{hint}",
+                tcx.def_path_str(def_id),
+                self.generic_args.0.len()
+            )));
+        }
+        for (idx, arg) in self.generic_args.0.iter().enumerate() {
+            // Skip the `Self` parameter.
+            let param = generics.param_at(idx + 1, tcx);
+            let matches = matches!(
+                (&param.kind, arg),
+                (rustc_ty::GenericParamDefKind::Lifetime, GenericArgKind::Lifetime(_))
+                    | (rustc_ty::GenericParamDefKind::Type { .. }, GenericArgKind::Type(_))
+                    | (rustc_ty::GenericParamDefKind::Const { .. }, GenericArgKind::Const(_))
+            );
+            if !matches {
+                return Err(Error::new(format!(
+                    "Expected a {} for parameter `{}` of `{}`, but found a {}",
+                    param.kind.descr(),
+                    param.name,
+                    tcx.def_path_str(def_id),
+                    generic_arg_descr(arg)
+                )));
+            }
+        }
+        Ok(self.internal(tables, tcx))
+    }
+}
+
 impl RustcInternal for TraitRef {
     type T<'tcx> = rustc_ty::TraitRef<'tcx>;
 
@@ -422,17 +1574,43 @@ fn internal<'tcx>(&self, tables: &mut Tables<'_>, tcx: TyCtxt<'tcx>) -> Self::T<
     }
 }
 
-impl RustcInternal for AllocId {
-    type T<'tcx> = rustc_middle::mir::interpret::AllocId;
-    fn internal<'tcx>(&self, tables: &mut Tables<'_>, tcx: TyCtxt<'tcx>) -> Self::T<'tcx> {
-        tcx.lift(tables.alloc_ids[*self]).unwrap()
+impl RustcInternalChecked<()> for TraitRef {
+    type T<'tcx> = rustc_ty::TraitRef<'tcx>;
+
+    /// Check that the arguments match the generic parameters of the trait, starting with the
+    /// `Self` type.
+    fn internal_checked<'tcx>(
+        &self,
+        _context: &(),
+        tables: &mut Tables<'_>,
+        tcx: TyCtxt<'tcx>,
+    ) -> Result<Self::T<'tcx>, Error> {
+        let def_id = self.def_id.0.internal(tables, tcx);
+        if !matches!(tcx.def_kind(def_id), DefKind::Trait | DefKind::TraitAlias) {
+            return Err(Error::new(format!(
+                "Expected a trait, but `{}` is a {}",
+                tcx.def_path_str(def_id),
+                tcx.def_descr(def_id)
+            )));
+        }
+        let expected = tcx.generics_of(def_id).count();
+        if self.args().0.len() + 1 == expected {
+            return Err(Error::new(format!(
+                "Expected {expected} generic arguments for `{}`, but found {}. Note that the \
+                `Self` type must be the first argument of a trait reference",
+                tcx.def_path_str(def_id),
+                self.args().0.len()
+            )));
+        }
+        let args = self.args().internal_checked(&self.def_id.0, tables, tcx)?;
+        Ok(rustc_ty::TraitRef::new_from_args(tcx, def_id, args))
     }
 }
 
-impl RustcInternal for ClosureKind {
-    type T<'tcx> = rustc_ty::ClosureKind;
+impl IntoInternal for ClosureKind {
+    type T = rustc_ty::ClosureKind;
 
-    fn internal<'tcx>(&self, _tables: &mut Tables<'_>, _tcx: TyCtxt<'tcx>) -> Self::T<'tcx> {
+    fn to_internal(&self) -> Self::T {
         match self {
             ClosureKind::Fn => rustc_ty::ClosureKind::Fn,
             ClosureKind::FnMut => rustc_ty::ClosureKind::FnMut,
@@ -448,10 +1626,24 @@ fn internal<'tcx>(&self, tables: &mut Tables<'_>, tcx: TyCtxt<'tcx>) -> Self::T<
     }
 }
 
-impl RustcInternal for Abi {
-    type T<'tcx> = rustc_target::spec::abi::Abi;
+impl IntoInternal for AdtKind {
+    type T = rustc_ty::AdtKind;
 
-    fn internal<'tcx>(&self, _tables: &mut Tables<'_>, _tcx: TyCtxt<'tcx>) -> Self::T<'tcx> {
+    fn to_internal(&self) -> Self::T {
+        match self {
+            AdtKind::Enum => rustc_ty::AdtKind::Enum,
+            AdtKind::Union => rustc_ty::AdtKind::Union,
+            AdtKind::Struct => rustc_ty::AdtKind::Struct,
+        }
+    }
+}
+
+impl IntoInternal for Abi {
+    type T = rustc_target::spec::abi::Abi;
+
+    /// The `unwind` flag is copied as is, and no validation is done. Only the ABIs that have an
+    /// unwinding variant, e.g., `C-unwind`, carry the flag, so every stable ABI is valid.
+    fn to_internal(&self) -> Self::T {
         match *self {
             Abi::Rust => rustc_target::spec::abi::Abi::Rust,
             Abi::C { unwind } => rustc_target::spec::abi::Abi::C { unwind },
@@ -481,29 +1673,290 @@ fn internal<'tcx>(&self, _tables: &mut Tables<'_>, _tcx: TyCtxt<'tcx>) -> Self::
     }
 }
 
-impl RustcInternal for Safety {
-    type T<'tcx> = rustc_hir::Safety;
+impl RustcInternalChecked<Abi> for CallConvention {
+    type T<'tcx> = rustc_target::abi::call::Conv;
+
+    /// The stable calling convention does not include the kind of a RISC-V interrupt, so it is
+    /// taken from the ABI of the function, which must be one of the RISC-V interrupt ABIs.
+    fn internal_checked<'tcx>(
+        &self,
+        abi: &Abi,
+        _tables: &mut Tables<'_>,
+        _tcx: TyCtxt<'tcx>,
+    ) -> Result<Self::T<'tcx>, Error> {
+        use rustc_target::abi::call::{Conv, RiscvInterruptKind};
+        Ok(match self {
+            CallConvention::C => Conv::C,
+            CallConvention::Rust => Conv::Rust,
+            CallConvention::Cold => Conv::Cold,
+            CallConvention::PreserveMost => Conv::PreserveMost,
+            CallConvention::PreserveAll => Conv::PreserveAll,
+            CallConvention::ArmAapcs => Conv::ArmAapcs,
+            CallConvention::CCmseNonSecureCall => Conv::CCmseNonSecureCall,
+            CallConvention::Msp430Intr => Conv::Msp430Intr,
+            CallConvention::PtxKernel => Conv::PtxKernel,
+            CallConvention::X86Fastcall => Conv::X86Fastcall,
+            CallConvention::X86Intr => Conv::X86Intr,
+            CallConvention::X86Stdcall => Conv::X86Stdcall,
+            CallConvention::X86ThisCall => Conv::X86ThisCall,
+            CallConvention::X86VectorCall => Conv::X86VectorCall,
+            CallConvention::X86_64SysV => Conv::X86_64SysV,
+            CallConvention::X86_64Win64 => Conv::X86_64Win64,
+            CallConvention::AvrInterrupt => Conv::AvrInterrupt,
+            CallConvention::AvrNonBlockingInterrupt => Conv::AvrNonBlockingInterrupt,
+            CallConvention::RiscvInterrupt => {
+                let kind = match abi {
+                    Abi::RiscvInterruptM => RiscvInterruptKind::Machine,
+                    Abi::RiscvInterruptS => RiscvInterruptKind::Supervisor,
+                    _ => {
+                        return Err(Error::new(format!(
+                            "Expected a RISC-V interrupt ABI for `{self:?}`, but found `{abi:?}`"
+                        )));
+                    }
+                };
+                Conv::RiscvInterrupt { kind }
+            }
+        })
+    }
+}
 
-    fn internal<'tcx>(&self, _tables: &mut Tables<'_>, _tcx: TyCtxt<'tcx>) -> Self::T<'tcx> {
+impl IntoInternal for Safety {
+    type T = rustc_hir::Safety;
+
+    fn to_internal(&self) -> Self::T {
         match self {
             Safety::Unsafe => rustc_hir::Safety::Unsafe,
             Safety::Safe => rustc_hir::Safety::Safe,
         }
     }
 }
-impl RustcInternal for Span {
-    type T<'tcx> = rustc_span::Span;
 
-    fn internal<'tcx>(&self, tables: &mut Tables<'_>, _tcx: TyCtxt<'tcx>) -> Self::T<'tcx> {
-        tables[*self]
+impl IntoInternal for MachineSize {
+    type T = rustc_abi::Size;
+
+    fn to_internal(&self) -> Self::T {
+        rustc_abi::Size::from_bits(self.bits() as u64)
+    }
+}
+
+impl IntoInternal for FieldsShape {
+    type T = rustc_abi::FieldsShape<rustc_target::abi::FieldIdx>;
+
+    fn to_internal(&self) -> Self::T {
+        match self {
+            FieldsShape::Primitive => rustc_abi::FieldsShape::Primitive,
+            FieldsShape::Union(count) => rustc_abi::FieldsShape::Union(*count),
+            FieldsShape::Array { stride, count } => {
+                rustc_abi::FieldsShape::Array { stride: stride.to_internal(), count: *count }
+            }
+            FieldsShape::Arbitrary { offsets, memory_index } => rustc_abi::FieldsShape::Arbitrary {
+                offsets: offsets.iter().map(|offset| offset.to_internal()).collect(),
+                memory_index: memory_index.iter().copied().collect(),
+            },
+        }
     }
 }
 
-impl RustcInternal for Layout {
-    type T<'tcx> = rustc_target::abi::Layout<'tcx>;
+impl IntoInternal for TagEncoding {
+    type T = rustc_abi::TagEncoding<rustc_target::abi::VariantIdx>;
 
-    fn internal<'tcx>(&self, tables: &mut Tables<'_>, tcx: TyCtxt<'tcx>) -> Self::T<'tcx> {
-        tcx.lift(tables.layouts[*self]).unwrap()
+    fn to_internal(&self) -> Self::T {
+        match self {
+            TagEncoding::Direct => rustc_abi::TagEncoding::Direct,
+            TagEncoding::Niche { untagged_variant, niche_variants, niche_start } => {
+                rustc_abi::TagEncoding::Niche {
+                    untagged_variant: untagged_variant.to_internal(),
+                    niche_variants: niche_variants.start().to_internal()
+                        ..=niche_variants.end().to_internal(),
+                    niche_start: *niche_start,
+                }
+            }
+        }
+    }
+}
+
+impl RustcInternalChecked<Layout> for VariantsShape {
+    type T<'tcx> = rustc_abi::Variants<rustc_target::abi::FieldIdx, rustc_target::abi::VariantIdx>;
+
+    /// The stable shape of each variant does not carry enough information to rebuild its internal
+    /// layout, so the variant layouts are taken from the layout this shape belongs to.
+    ///
+    /// Check that the layout has the same kind of variants, and for multiple variants, that it
+    /// has the same number of variants.
+    fn internal_checked<'tcx>(
+        &self,
+        context: &Layout,
+        tables: &mut Tables<'_>,
+        tcx: TyCtxt<'tcx>,
+    ) -> Result<Self::T<'tcx>, Error> {
+        let layout = context.internal(tables, tcx);
+        match (self, layout.variants()) {
+            (VariantsShape::Single { index }, rustc_abi::Variants::Single { .. }) => {
+                Ok(rustc_abi::Variants::Single { index: index.internal(tables, tcx) })
+            }
+            (
+                VariantsShape::Multiple { tag, tag_encoding, tag_field, variants },
+                rustc_abi::Variants::Multiple { variants: layout_variants, .. },
+            ) => {
+                if variants.len() != layout_variants.len() {
+                    return Err(Error::new(format!(
+                        "Expected {} variants to match the layout `{context:?}`, but found {}",
+                        layout_variants.len(),
+                        variants.len()
+                    )));
+                }
+                Ok(rustc_abi::Variants::Multiple {
+                    tag: tag.internal(tables, tcx),
+                    tag_encoding: tag_encoding.internal(tables, tcx),
+                    tag_field: *tag_field,
+                    variants: layout_variants.clone(),
+                })
+            }
+            (shape, _) => Err(Error::new(format!(
+                "Variants `{shape:?}` do not match the variants of the layout `{context:?}`"
+            ))),
+        }
+    }
+}
+
+impl RustcInternalChecked<()> for ReprOptions {
+    type T<'tcx> = rustc_abi::ReprOptions;
+
+    /// Check that the requested alignment and packing are valid alignments.
+    ///
+    /// Note that the seed used to randomize the layout is not part of the stable representation,
+    /// so the result never requests a randomized layout.
+    fn internal_checked<'tcx>(
+        &self,
+        _context: &(),
+        tables: &mut Tables<'_>,
+        tcx: TyCtxt<'tcx>,
+    ) -> Result<Self::T<'tcx>, Error> {
+        let mut flags = rustc_abi::ReprFlags::empty();
+        flags.set(rustc_abi::ReprFlags::IS_SIMD, self.flags.is_simd);
+        flags.set(rustc_abi::ReprFlags::IS_C, self.flags.is_c);
+        flags.set(rustc_abi::ReprFlags::IS_TRANSPARENT, self.flags.is_transparent);
+        flags.set(rustc_abi::ReprFlags::IS_LINEAR, self.flags.is_linear);
+        let align = self.align.map(internal_align).transpose()?;
+        let pack = self.pack.map(internal_align).transpose()?;
+        Ok(rustc_abi::ReprOptions {
+            int: self.int.internal(tables, tcx),
+            align,
+            pack,
+            flags,
+            field_shuffle_seed: 0,
+        })
+    }
+}
+
+impl IntoInternal for IntegerType {
+    type T = rustc_abi::IntegerType;
+
+    fn to_internal(&self) -> Self::T {
+        match *self {
+            IntegerType::Pointer { is_signed } => rustc_abi::IntegerType::Pointer(is_signed),
+            IntegerType::Fixed { length, is_signed } => {
+                rustc_abi::IntegerType::Fixed(length.to_internal(), is_signed)
+            }
+        }
+    }
+}
+
+impl IntoInternal for IntegerLength {
+    type T = rustc_abi::Integer;
+
+    fn to_internal(&self) -> Self::T {
+        match self {
+            IntegerLength::I8 => rustc_abi::Integer::I8,
+            IntegerLength::I16 => rustc_abi::Integer::I16,
+            IntegerLength::I32 => rustc_abi::Integer::I32,
+            IntegerLength::I64 => rustc_abi::Integer::I64,
+            IntegerLength::I128 => rustc_abi::Integer::I128,
+        }
+    }
+}
+
+impl IntoInternal for Scalar {
+    type T = rustc_abi::Scalar;
+
+    fn to_internal(&self) -> Self::T {
+        match self {
+            Scalar::Initialized { value, valid_range } => rustc_abi::Scalar::Initialized {
+                value: value.to_internal(),
+                valid_range: valid_range.to_internal(),
+            },
+            Scalar::Union { value } => rustc_abi::Scalar::Union { value: value.to_internal() },
+        }
+    }
+}
+
+impl RustcInternalChecked<()> for Scalar {
+    type T<'tcx> = rustc_abi::Scalar;
+
+    /// Check that the valid range fits in the size of the primitive.
+    fn internal_checked<'tcx>(
+        &self,
+        _context: &(),
+        tables: &mut Tables<'_>,
+        tcx: TyCtxt<'tcx>,
+    ) -> Result<Self::T<'tcx>, Error> {
+        let scalar = self.internal(tables, tcx);
+        if let rustc_abi::Scalar::Initialized { value, valid_range } = scalar {
+            let size = value.size(&tcx);
+            let max = size.unsigned_int_max();
+            if valid_range.start > max || valid_range.end > max {
+                return Err(Error::new(format!(
+                    "Valid range `{}..={}` does not fit in a {}-bit primitive",
+                    valid_range.start,
+                    valid_range.end,
+                    size.bits()
+                )));
+            }
+        }
+        Ok(scalar)
+    }
+}
+
+impl IntoInternal for WrappingRange {
+    type T = rustc_abi::WrappingRange;
+
+    fn to_internal(&self) -> Self::T {
+        rustc_abi::WrappingRange { start: self.start, end: self.end }
+    }
+}
+
+impl IntoInternal for Primitive {
+    type T = rustc_abi::Primitive;
+
+    fn to_internal(&self) -> Self::T {
+        match self {
+            Primitive::Int { length, signed } => {
+                rustc_abi::Primitive::Int(length.to_internal(), *signed)
+            }
+            Primitive::Float { length } => rustc_abi::Primitive::Float(length.to_internal()),
+            Primitive::Pointer(space) => rustc_abi::Primitive::Pointer(space.to_internal()),
+        }
+    }
+}
+
+impl IntoInternal for FloatLength {
+    type T = rustc_abi::Float;
+
+    fn to_internal(&self) -> Self::T {
+        match self {
+            FloatLength::F16 => rustc_abi::Float::F16,
+            FloatLength::F32 => rustc_abi::Float::F32,
+            FloatLength::F64 => rustc_abi::Float::F64,
+            FloatLength::F128 => rustc_abi::Float::F128,
+        }
+    }
+}
+
+impl IntoInternal for AddressSpace {
+    type T = rustc_abi::AddressSpace;
+
+    fn to_internal(&self) -> Self::T {
+        rustc_abi::AddressSpace(self.0)
     }
 }
 
@@ -518,6 +1971,172 @@ fn internal<'tcx>(&self, tables: &mut Tables<'_>, tcx: TyCtxt<'tcx>) -> Self::T<
     }
 }
 
+impl RustcInternalChecked<Body> for Place {
+    type T<'tcx> = rustc_middle::mir::Place<'tcx>;
+
+    /// Check that the place local, as well as the locals used to index it, are declared in the
+    /// given body, and that index locals have type `usize`.
+    fn internal_checked<'tcx>(
+        &self,
+        body: &Body,
+        tables: &mut Tables<'_>,
+        tcx: TyCtxt<'tcx>,
+    ) -> Result<Self::T<'tcx>, Error> {
+        let index_locals = self.projection.iter().filter_map(|elem| match elem {
+            ProjectionElem::Index(local) => Some(*local),
+            _ => None,
+        });
+        for local in std::iter::once(self.local).chain(index_locals.clone()) {
+            if local >= body.locals().len() {
+                return Err(Error::new(format!(
+                    "Local `_{local}` is out of range. The body only has {} locals",
+                    body.locals().len()
+                )));
+            }
+        }
+        for local in index_locals {
+            let ty = body.locals()[local].ty.internal(tables, tcx);
+            if ty != tcx.types.usize {
+                return Err(Error::new(format!(
+                    "Expected index local `_{local}` to have type `usize`, but found `{ty}`"
+                )));
+            }
+        }
+        Ok(self.internal(tables, tcx))
+    }
+}
+
+impl RustcInternal for ConstOperand {
+    type T<'tcx> = rustc_middle::mir::ConstOperand<'tcx>;
+
+    fn internal<'tcx>(&self, tables: &mut Tables<'_>, tcx: TyCtxt<'tcx>) -> Self::T<'tcx> {
+        rustc_middle::mir::ConstOperand {
+            span: self.span.internal(tables, tcx),
+            user_ty: self.user_ty.map(rustc_ty::UserTypeAnnotationIndex::from_usize),
+            const_: self.const_.internal(tables, tcx),
+        }
+    }
+}
+
+impl RustcInternal for SourceInfo {
+    type T<'tcx> = rustc_middle::mir::SourceInfo;
+
+    fn internal<'tcx>(&self, tables: &mut Tables<'_>, tcx: TyCtxt<'tcx>) -> Self::T<'tcx> {
+        rustc_middle::mir::SourceInfo {
+            span: self.span.internal(tables, tcx),
+            scope: rustc_middle::mir::SourceScope::from_u32(self.scope),
+        }
+    }
+}
+
+impl RustcInternal for VarDebugInfo {
+    type T<'tcx> = rustc_middle::mir::VarDebugInfo<'tcx>;
+
+    fn internal<'tcx>(&self, tables: &mut Tables<'_>, tcx: TyCtxt<'tcx>) -> Self::T<'tcx> {
+        rustc_middle::mir::VarDebugInfo {
+            name: self.name.internal(tables, tcx),
+            source_info: self.source_info.internal(tables, tcx),
+            composite: self
+                .composite
+                .as_ref()
+                .map(|composite| Box::new(composite.internal(tables, tcx))),
+            value: self.value.internal(tables, tcx),
+            argument_index: self.argument_index,
+        }
+    }
+}
+
+impl RustcInternal for VarDebugInfoFragment {
+    type T<'tcx> = rustc_middle::mir::VarDebugInfoFragment<'tcx>;
+
+    fn internal<'tcx>(&self, tables: &mut Tables<'_>, tcx: TyCtxt<'tcx>) -> Self::T<'tcx> {
+        rustc_middle::mir::VarDebugInfoFragment {
+            ty: self.ty.internal(tables, tcx),
+            projection: self.projection.iter().map(|elem| elem.internal(tables, tcx)).collect(),
+        }
+    }
+}
+
+impl RustcInternal for VarDebugInfoContents {
+    type T<'tcx> = rustc_middle::mir::VarDebugInfoContents<'tcx>;
+
+    fn internal<'tcx>(&self, tables: &mut Tables<'_>, tcx: TyCtxt<'tcx>) -> Self::T<'tcx> {
+        match self {
+            VarDebugInfoContents::Place(place) => {
+                rustc_middle::mir::VarDebugInfoContents::Place(place.internal(tables, tcx))
+            }
+            VarDebugInfoContents::Const(const_operand) => {
+                rustc_middle::mir::VarDebugInfoContents::Const(const_operand.internal(tables, tcx))
+            }
+        }
+    }
+}
+
+impl IntoInternal for SwitchTargets {
+    type T = rustc_middle::mir::SwitchTargets;
+
+    fn to_internal(&self) -> Self::T {
+        rustc_middle::mir::SwitchTargets::new(
+            self.branches()
+                .map(|(value, target)| (value, rustc_middle::mir::BasicBlock::from_usize(target))),
+            rustc_middle::mir::BasicBlock::from_usize(self.otherwise()),
+        )
+    }
+}
+
+impl RustcInternalChecked<Body> for SwitchTargets {
+    type T<'tcx> = rustc_middle::mir::SwitchTargets;
+
+    /// Check that every target, including `otherwise`, is a block of the given body.
+    fn internal_checked<'tcx>(
+        &self,
+        body: &Body,
+        tables: &mut Tables<'_>,
+        tcx: TyCtxt<'tcx>,
+    ) -> Result<Self::T<'tcx>, Error> {
+        if let Some(target) = self.all_targets().into_iter().find(|bb| *bb >= body.blocks.len()) {
+            return Err(Error::new(format!(
+                "Target `bb{target}` is out of range. The body only has {} blocks",
+                body.blocks.len()
+            )));
+        }
+        Ok(self.internal(tables, tcx))
+    }
+}
+
+impl RustcInternalChecked<Ty> for SwitchTargets {
+    type T<'tcx> = rustc_middle::mir::SwitchTargets;
+
+    /// Check that every branch value is a valid value of the given discriminant type.
+    fn internal_checked<'tcx>(
+        &self,
+        discr_ty: &Ty,
+        tables: &mut Tables<'_>,
+        tcx: TyCtxt<'tcx>,
+    ) -> Result<Self::T<'tcx>, Error> {
+        let ty = discr_ty.internal(tables, tcx);
+        if !ty.is_integral() && !ty.is_bool() && !ty.is_char() {
+            return Err(Error::new(format!("Cannot switch on a value of type `{ty}`")));
+        }
+        let size = ty.primitive_size(tcx);
+        let is_valid = |value: u128| {
+            if ty.is_bool() {
+                value <= 1
+            } else if ty.is_char() {
+                u32::try_from(value).ok().and_then(char::from_u32).is_some()
+            } else {
+                size.truncate(value) == value
+            }
+        };
+        if let Some((value, _)) = self.branches().find(|(value, _)| !is_valid(*value)) {
+            return Err(Error::new(format!(
+                "Value `{value}` is not a valid value of the discriminant type `{ty}`"
+            )));
+        }
+        Ok(self.internal(tables, tcx))
+    }
+}
+
 impl RustcInternal for ProjectionElem {
     type T<'tcx> = rustc_middle::mir::PlaceElem<'tcx>;
 
@@ -551,17 +2170,20 @@ fn internal<'tcx>(&self, tables: &mut Tables<'_>, tcx: TyCtxt<'tcx>) -> Self::T<
     }
 }
 
-impl RustcInternal for BinOp {
-    type T<'tcx> = rustc_middle::mir::BinOp;
+impl IntoInternal for BinOp {
+    type T = rustc_middle::mir::BinOp;
 
-    fn internal<'tcx>(&self, _tables: &mut Tables<'_>, _tcx: TyCtxt<'tcx>) -> Self::T<'tcx> {
+    fn to_internal(&self) -> Self::T {
         match self {
             BinOp::Add => rustc_middle::mir::BinOp::Add,
             BinOp::AddUnchecked => rustc_middle::mir::BinOp::AddUnchecked,
+            BinOp::AddWithOverflow => rustc_middle::mir::BinOp::AddWithOverflow,
             BinOp::Sub => rustc_middle::mir::BinOp::Sub,
             BinOp::SubUnchecked => rustc_middle::mir::BinOp::SubUnchecked,
+            BinOp::SubWithOverflow => rustc_middle::mir::BinOp::SubWithOverflow,
             BinOp::Mul => rustc_middle::mir::BinOp::Mul,
             BinOp::MulUnchecked => rustc_middle::mir::BinOp::MulUnchecked,
+            BinOp::MulWithOverflow => rustc_middle::mir::BinOp::MulWithOverflow,
             BinOp::Div => rustc_middle::mir::BinOp::Div,
             BinOp::Rem => rustc_middle::mir::BinOp::Rem,
             BinOp::BitXor => rustc_middle::mir::BinOp::BitXor,
@@ -583,10 +2205,23 @@ fn internal<'tcx>(&self, _tables: &mut Tables<'_>, _tcx: TyCtxt<'tcx>) -> Self::
     }
 }
 
-impl RustcInternal for UnOp {
-    type T<'tcx> = rustc_middle::mir::UnOp;
+impl IntoInternal for Variance {
+    type T = rustc_ty::Variance;
 
-    fn internal<'tcx>(&self, _tables: &mut Tables<'_>, _tcx: TyCtxt<'tcx>) -> Self::T<'tcx> {
+    fn to_internal(&self) -> Self::T {
+        match self {
+            Variance::Covariant => rustc_ty::Variance::Covariant,
+            Variance::Invariant => rustc_ty::Variance::Invariant,
+            Variance::Contravariant => rustc_ty::Variance::Contravariant,
+            Variance::Bivariant => rustc_ty::Variance::Bivariant,
+        }
+    }
+}
+
+impl IntoInternal for UnOp {
+    type T = rustc_middle::mir::UnOp;
+
+    fn to_internal(&self) -> Self::T {
         match self {
             UnOp::Not => rustc_middle::mir::UnOp::Not,
             UnOp::Neg => rustc_middle::mir::UnOp::Neg,
@@ -595,6 +2230,43 @@ fn internal<'tcx>(&self, _tables: &mut Tables<'_>, _tcx: TyCtxt<'tcx>) -> Self::
     }
 }
 
+impl IntoInternal for BorrowKind {
+    type T = rustc_middle::mir::BorrowKind;
+
+    fn to_internal(&self) -> Self::T {
+        match self {
+            BorrowKind::Shared => rustc_middle::mir::BorrowKind::Shared,
+            BorrowKind::Fake(kind) => rustc_middle::mir::BorrowKind::Fake(kind.to_internal()),
+            BorrowKind::Mut { kind } => {
+                rustc_middle::mir::BorrowKind::Mut { kind: kind.to_internal() }
+            }
+        }
+    }
+}
+
+impl IntoInternal for MutBorrowKind {
+    type T = rustc_middle::mir::MutBorrowKind;
+
+    fn to_internal(&self) -> Self::T {
+        match self {
+            MutBorrowKind::Default => rustc_middle::mir::MutBorrowKind::Default,
+            MutBorrowKind::TwoPhaseBorrow => rustc_middle::mir::MutBorrowKind::TwoPhaseBorrow,
+            MutBorrowKind::ClosureCapture => rustc_middle::mir::MutBorrowKind::ClosureCapture,
+        }
+    }
+}
+
+impl IntoInternal for FakeBorrowKind {
+    type T = rustc_middle::mir::FakeBorrowKind;
+
+    fn to_internal(&self) -> Self::T {
+        match self {
+            FakeBorrowKind::Deep => rustc_middle::mir::FakeBorrowKind::Deep,
+            FakeBorrowKind::Shallow => rustc_middle::mir::FakeBorrowKind::Shallow,
+        }
+    }
+}
+
 impl<T> RustcInternal for &T
 where
     T: RustcInternal,
@@ -606,6 +2278,23 @@ fn internal<'tcx>(&self, tables: &mut Tables<'_>, tcx: TyCtxt<'tcx>) -> Self::T<
     }
 }
 
+impl<T, C> RustcInternalChecked<C> for &T
+where
+    T: RustcInternalChecked<C>,
+    C: ?Sized,
+{
+    type T<'tcx> = T::T<'tcx>;
+
+    fn internal_checked<'tcx>(
+        &self,
+        context: &C,
+        tables: &mut Tables<'_>,
+        tcx: TyCtxt<'tcx>,
+    ) -> Result<Self::T<'tcx>, Error> {
+        (*self).internal_checked(context, tables, tcx)
+    }
+}
+
 impl<T> RustcInternal for Option<T>
 where
     T: RustcInternal,
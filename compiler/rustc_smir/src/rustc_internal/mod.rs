@@ -10,9 +10,13 @@
 
 use rustc_data_structures::fx;
 use rustc_data_structures::fx::FxIndexMap;
-use rustc_middle::mir::interpret::AllocId;
+use rustc_hir::def::DefKind;
+use rustc_middle::mir::interpret::{
+    alloc_range, read_target_uint, AllocId, AllocRange, Allocation, Pointer, Scalar,
+};
+use rustc_middle::mir::ConstValue;
 use rustc_middle::ty;
-use rustc_middle::ty::TyCtxt;
+use rustc_middle::ty::{TyCtxt, TypeVisitableExt};
 use rustc_span::def_id::{CrateNum, DefId};
 use rustc_span::Span;
 use scoped_tls::scoped_thread_local;
@@ -60,6 +64,620 @@ pub fn internal<'tcx, S>(tcx: TyCtxt<'tcx>, item: S) -> S::T<'tcx>
     with_tables(|tables| item.internal(tables, tcx))
 }
 
+/// Convert a stable item into its internal Rust compiler counterpart after validating that the
+/// item is well-formed in the given context.
+///
+/// This is similar to [internal], but it returns an error instead of producing a malformed
+/// internal item that may trigger an ICE later.
+///
+/// # Warning
+///
+/// This function is unstable, and it's behavior may change at any point.
+/// Not every stable item can be converted to an internal one.
+/// Furthermore, items that were previously supported, may no longer be supported in newer versions.
+///
+/// # Panics
+///
+/// This function will panic if StableMIR has not been properly initialized.
+pub fn internal_checked<'tcx, S, C>(
+    tcx: TyCtxt<'tcx>,
+    item: S,
+    context: &C,
+) -> Result<S::T<'tcx>, Error>
+where
+    S: RustcInternalChecked<C>,
+    C: ?Sized,
+{
+    // The tcx argument ensures that the item won't outlive the type context.
+    with_tables(|tables| item.internal_checked(context, tables, tcx))
+}
+
+/// Convert a stable type into its internal Rust compiler counterpart and normalize it in the given
+/// parameter environment.
+///
+/// Regions are erased from the normalized type. The returned flag is `false` if the type could not
+/// be normalized, e.g., when a projection is ambiguous or its trait bound does not hold. In that
+/// case, the converted type is returned as is.
+///
+/// # Warning
+///
+/// This function is unstable, and it's behavior may change at any point.
+///
+/// # Panics
+///
+/// This function will panic if StableMIR has not been properly initialized.
+pub fn internal_normalized<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    ty: stable_mir::ty::Ty,
+    param_env: &stable_mir::ty::ParamEnv,
+) -> (ty::Ty<'tcx>, bool) {
+    // The tcx argument ensures that the type won't outlive the type context.
+    with_tables(|tables| {
+        let ty = ty.internal(tables, tcx);
+        let param_env = param_env.internal(tables, tcx);
+        match tcx.try_normalize_erasing_regions(param_env, ty) {
+            Ok(normalized) => (normalized, true),
+            Err(_) => (ty, false),
+        }
+    })
+}
+
+/// The internal representation of a variant together with its discriminant and its fields.
+#[derive(Clone, Debug)]
+pub struct InternalVariant<'tcx> {
+    pub def: &'tcx ty::VariantDef,
+    /// The discriminant of the variant, which is only defined for enum variants.
+    pub discr: Option<ty::util::Discr<'tcx>>,
+    pub fields: Vec<&'tcx ty::FieldDef>,
+}
+
+/// Convert a stable variant definition into its internal Rust compiler counterpart, together with
+/// its discriminant and its fields.
+///
+/// # Warning
+///
+/// This function is unstable, and it's behavior may change at any point.
+///
+/// # Panics
+///
+/// This function will panic if StableMIR has not been properly initialized.
+pub fn internal_variant<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    variant: stable_mir::ty::VariantDef,
+) -> InternalVariant<'tcx> {
+    // The tcx argument ensures that the variant won't outlive the type context.
+    with_tables(|tables| {
+        let adt_def = variant.adt_def.internal(tables, tcx);
+        let idx = variant.idx.internal(tables, tcx);
+        let def = adt_def.variant(idx);
+        let discr = adt_def.is_enum().then(|| adt_def.discriminant_for_variant(tcx, idx));
+        InternalVariant { def, discr, fields: def.fields.iter().collect() }
+    })
+}
+
+/// Convert stable generic arguments for the given definition into their internal Rust compiler
+/// counterpart, filling in the defaults of omitted trailing type and const parameters.
+///
+/// The resulting arguments are checked to match the generic parameters of the definition, which
+/// fails if an omitted parameter has no default.
+///
+/// # Warning
+///
+/// This function is unstable, and it's behavior may change at any point.
+///
+/// # Panics
+///
+/// This function will panic if StableMIR has not been properly initialized.
+pub fn internal_args_with_defaults<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    def: stable_mir::DefId,
+    args: &stable_mir::ty::GenericArgs,
+) -> Result<ty::GenericArgsRef<'tcx>, Error> {
+    // The tcx argument ensures that the arguments won't outlive the type context.
+    with_tables(|tables| {
+        let def_id = def.internal(tables, tcx);
+        let generics = tcx.generics_of(def_id);
+        let mut internal_args = args.internal(tables, tcx).to_vec();
+        for idx in internal_args.len()..generics.count() {
+            let param = generics.param_at(idx, tcx);
+            let Some(default) = param.default_value(tcx) else {
+                return Err(Error::new(format!(
+                    "Missing generic argument for parameter `{}` of `{}`, which has no default",
+                    param.name,
+                    tcx.def_path_str(def_id)
+                )));
+            };
+            // Defaults may refer to the parameters that come before them.
+            internal_args.push(default.instantiate(tcx, &internal_args[..]));
+        }
+        internal::check_args(tcx, def_id, &internal_args)?;
+        Ok(tcx.mk_args(&internal_args))
+    })
+}
+
+/// Build the internal function mono item of the instance with the given kind, definition and
+/// generic arguments.
+///
+/// Unlike converting a [stable_mir::mir::mono::MonoItem], this does not require the instance to
+/// have been created by the compiler already. The arguments must be fully monomorphized, and the
+/// instance must be the one the definition resolves to. E.g., a trait method resolves to the
+/// method of the implementation, so it is rejected.
+///
+/// # Warning
+///
+/// This function is unstable, and it's behavior may change at any point.
+///
+/// # Panics
+///
+/// This function will panic if StableMIR has not been properly initialized.
+pub fn internal_mono_fn<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    kind: stable_mir::mir::mono::InstanceKind,
+    def: stable_mir::DefId,
+    args: &stable_mir::ty::GenericArgs,
+) -> Result<rustc_middle::mir::mono::MonoItem<'tcx>, Error> {
+    // The tcx argument ensures that the item won't outlive the type context.
+    with_tables(|tables| {
+        let internal_args = internal_mono_args(tables, tcx, def, args)?;
+        let instance_kind = kind.internal_checked(&(def, args.clone()), tables, tcx)?;
+        let instance = ty::Instance { def: instance_kind, args: internal_args };
+        let def_id = def.internal(tables, tcx);
+        match ty::Instance::try_resolve(tcx, ty::ParamEnv::reveal_all(), def_id, internal_args) {
+            Ok(Some(resolved)) if resolved == instance => {
+                Ok(rustc_middle::mir::mono::MonoItem::Fn(instance))
+            }
+            Ok(Some(resolved)) => Err(Error::new(format!(
+                "Expected `{instance}` to resolve to itself, but it resolves to `{resolved}`"
+            ))),
+            Ok(None) | Err(_) => {
+                Err(Error::new(format!("Failed to resolve `{instance}` to a function")))
+            }
+        }
+    })
+}
+
+/// Convert the generic arguments of a compiler generated shim, e.g., the drop glue of a type, into
+/// their internal Rust compiler counterpart, ready to be used in an [ty::Instance].
+///
+/// The arguments must match the generics of the definition, be fully monomorphized, and resolve
+/// to a shim.
+///
+/// # Warning
+///
+/// This function is unstable, and it's behavior may change at any point.
+///
+/// # Panics
+///
+/// This function will panic if StableMIR has not been properly initialized.
+pub fn internal_shim_args<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    def: stable_mir::DefId,
+    args: &stable_mir::ty::GenericArgs,
+) -> Result<ty::GenericArgsRef<'tcx>, Error> {
+    // The tcx argument ensures that the arguments won't outlive the type context.
+    with_tables(|tables| {
+        let internal_args = internal_mono_args(tables, tcx, def, args)?;
+        stable_mir::mir::mono::InstanceKind::Shim.internal_checked(
+            &(def, args.clone()),
+            tables,
+            tcx,
+        )?;
+        Ok(internal_args)
+    })
+}
+
+/// Convert generic arguments for the given definition, checking that they are monomorphic.
+fn internal_mono_args<'tcx>(
+    tables: &mut Tables<'_>,
+    tcx: TyCtxt<'tcx>,
+    def: stable_mir::DefId,
+    args: &stable_mir::ty::GenericArgs,
+) -> Result<ty::GenericArgsRef<'tcx>, Error> {
+    let internal_args = args.internal_checked(&def, tables, tcx)?;
+    if internal_args.has_param() {
+        return Err(Error::new(format!(
+            "Expected monomorphic arguments for `{}`, but found `{internal_args:?}`",
+            tcx.def_path_str(def.internal(tables, tcx))
+        )));
+    }
+    Ok(internal_args)
+}
+
+/// Build the internal type that encodes the given closure kind in the arguments of a closure, i.e.,
+/// the closure kind argument of [ty::ClosureArgs].
+///
+/// # Warning
+///
+/// This function is unstable, and it's behavior may change at any point.
+///
+/// # Panics
+///
+/// This function will panic if StableMIR has not been properly initialized.
+pub fn internal_closure_kind_ty<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    kind: stable_mir::ty::ClosureKind,
+) -> ty::Ty<'tcx> {
+    // The tcx argument ensures that the type won't outlive the type context.
+    with_tables(|tables| ty::Ty::from_closure_kind(tcx, kind.internal(tables, tcx)))
+}
+
+/// Compute the internal signature of a function definition instantiated with the given generic
+/// arguments, i.e., the signature implied by the function type `RigidTy::FnDef(def, args)`.
+///
+/// # Warning
+///
+/// This function is unstable, and it's behavior may change at any point.
+///
+/// # Panics
+///
+/// This function will panic if StableMIR has not been properly initialized.
+pub fn internal_fn_sig<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    def: stable_mir::ty::FnDef,
+    args: &stable_mir::ty::GenericArgs,
+) -> Result<ty::PolyFnSig<'tcx>, Error> {
+    // The tcx argument ensures that the signature won't outlive the type context.
+    with_tables(|tables| {
+        let internal_args = args.internal_checked(&def.0, tables, tcx)?;
+        Ok(tcx.fn_sig(def.0.internal(tables, tcx)).instantiate(tcx, internal_args))
+    })
+}
+
+/// Retrieve the internal associated items of a stable trait definition, i.e., its methods,
+/// associated types and associated constants.
+///
+/// # Warning
+///
+/// This function is unstable, and it's behavior may change at any point.
+///
+/// # Panics
+///
+/// This function will panic if StableMIR has not been properly initialized.
+pub fn internal_assoc_items<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    def: stable_mir::ty::TraitDef,
+) -> Result<&'tcx ty::AssocItems, Error> {
+    // The tcx argument ensures that the items won't outlive the type context.
+    with_tables(|tables| {
+        let def_id = def.0.internal(tables, tcx);
+        if tcx.def_kind(def_id) != DefKind::Trait {
+            return Err(Error::new(format!(
+                "Expected a trait, but `{}` is a {}",
+                tcx.def_path_str(def_id),
+                tcx.def_descr(def_id)
+            )));
+        }
+        Ok(tcx.associated_items(def_id))
+    })
+}
+
+/// Convert a stable MIR constant into its internal Rust compiler counterpart, paired with its
+/// internal type.
+///
+/// # Warning
+///
+/// This function is unstable, and it's behavior may change at any point.
+///
+/// # Panics
+///
+/// This function will panic if StableMIR has not been properly initialized.
+pub fn internal_mir_const_with_ty<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    constant: &stable_mir::ty::MirConst,
+) -> (rustc_middle::mir::Const<'tcx>, ty::Ty<'tcx>) {
+    // The tcx argument ensures that the constant won't outlive the type context.
+    with_tables(|tables| {
+        let internal = constant.internal(tables, tcx);
+        (internal, internal.ty())
+    })
+}
+
+/// Convert an unevaluated constant into its internal Rust compiler counterpart, paired with the
+/// internal environment it should be evaluated in, e.g., using `TyCtxt::const_eval_resolve`.
+///
+/// This allows evaluating a constant with different generic arguments than the ones it was used
+/// with, such as the arguments of a monomorphic instance. The arguments must match the generic
+/// parameters of the constant.
+///
+/// # Warning
+///
+/// This function is unstable, and it's behavior may change at any point.
+///
+/// # Panics
+///
+/// This function will panic if StableMIR has not been properly initialized.
+pub fn internal_unevaluated_const<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    constant: &stable_mir::ty::UnevaluatedConst,
+    param_env: &stable_mir::ty::ParamEnv,
+) -> Result<(ty::ParamEnv<'tcx>, rustc_middle::mir::UnevaluatedConst<'tcx>), Error> {
+    // The tcx argument ensures that the constant won't outlive the type context.
+    with_tables(|tables| {
+        let args = constant.args.internal_checked(&constant.def.0, tables, tcx)?;
+        let def = constant.def.0.internal(tables, tcx);
+        let promoted = constant.promoted.map(rustc_middle::mir::Promoted::from_u32);
+        let uneval = rustc_middle::mir::UnevaluatedConst { def, args, promoted };
+        Ok((param_env.internal(tables, tcx), uneval))
+    })
+}
+
+/// Convert a local of the given stable body into its internal Rust compiler counterpart, paired
+/// with the internal type of its declaration.
+///
+/// # Warning
+///
+/// This function is unstable, and it's behavior may change at any point.
+///
+/// # Panics
+///
+/// This function will panic if StableMIR has not been properly initialized.
+pub fn internal_local<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    local: stable_mir::mir::Local,
+    body: &stable_mir::mir::Body,
+) -> Result<(rustc_middle::mir::Local, ty::Ty<'tcx>), Error> {
+    let Some(decl) = body.locals().get(local) else {
+        return Err(Error::new(format!(
+            "Local `_{local}` is out of range. The body only has {} locals",
+            body.locals().len()
+        )));
+    };
+    // The tcx argument ensures that the type won't outlive the type context.
+    with_tables(|tables| {
+        Ok((rustc_middle::mir::Local::from_usize(local), decl.ty.internal(tables, tcx)))
+    })
+}
+
+/// Convert a source scope of the body of the given instance into its internal Rust compiler
+/// counterpart, checking that the scope is declared by the internal body of the instance.
+///
+/// Return an error if the instance has no body, e.g., for intrinsics and virtual calls.
+///
+/// # Warning
+///
+/// This function is unstable, and it's behavior may change at any point.
+///
+/// # Panics
+///
+/// This function will panic if StableMIR has not been properly initialized.
+pub fn internal_source_scope<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    scope: stable_mir::mir::SourceScope,
+    instance: stable_mir::mir::mono::Instance,
+) -> Result<rustc_middle::mir::SourceScope, Error> {
+    // The tcx argument ensures that the scope won't outlive the type context.
+    with_tables(|tables| {
+        let instance = instance.internal(tables, tcx);
+        if !tables.instance_has_body(instance) {
+            return Err(Error::new(format!("Instance `{instance}` has no body")));
+        }
+        let scopes = &tcx.instance_mir(instance.def).source_scopes;
+        if scope as usize >= scopes.len() {
+            return Err(Error::new(format!(
+                "Source scope `{scope}` is out of range. The body of `{instance}` only has {} \
+                scopes",
+                scopes.len()
+            )));
+        }
+        Ok(rustc_middle::mir::SourceScope::from_u32(scope))
+    })
+}
+
+/// Compute the internal type of the discriminant read from the given place, i.e., the type of the
+/// result of `Rvalue::Discriminant(place)`.
+///
+/// For enums, this is the type of their discriminant, e.g., `i16` for a `#[repr(i16)]` enum. For
+/// other types, this is `u8`.
+///
+/// # Warning
+///
+/// This function is unstable, and it's behavior may change at any point.
+///
+/// # Panics
+///
+/// This function will panic if StableMIR has not been properly initialized.
+pub fn internal_discriminant_ty<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    place: &stable_mir::mir::Place,
+    body: &stable_mir::mir::Body,
+) -> Result<ty::Ty<'tcx>, Error> {
+    // The tcx argument ensures that the type won't outlive the type context.
+    with_tables(|tables| place.internal_checked(body, tables, tcx))?;
+    // Computing the type of the place requires the tables, so it cannot be done while converting.
+    let place_ty = place.ty(body.locals())?;
+    with_tables(|tables| Ok(place_ty.internal(tables, tcx).discriminant_ty(tcx)))
+}
+
+/// Convert a stable rigid type kind into the internal Rust compiler type of that kind, interned in
+/// the given type context.
+///
+/// # Warning
+///
+/// This function is unstable, and it's behavior may change at any point.
+///
+/// # Panics
+///
+/// This function will panic if StableMIR has not been properly initialized.
+pub fn internal_rigid_ty<'tcx>(tcx: TyCtxt<'tcx>, rigid: &stable_mir::ty::RigidTy) -> ty::Ty<'tcx> {
+    // The tcx argument ensures that the type won't outlive the type context.
+    with_tables(|tables| tcx.mk_ty_from_kind(rigid.internal(tables, tcx)))
+}
+
+/// Compute the internal type of the metadata of pointers to the given pointee type, e.g., `usize`
+/// for slices and `str`, `DynMetadata<dyn Trait>` for trait objects, and `()` for sized types.
+///
+/// The pointee type must be fully monomorphized so the tail of the type can be determined.
+///
+/// # Warning
+///
+/// This function is unstable, and it's behavior may change at any point.
+///
+/// # Panics
+///
+/// This function will panic if StableMIR has not been properly initialized.
+pub fn internal_ptr_metadata_ty<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    pointee: stable_mir::ty::Ty,
+) -> Result<ty::Ty<'tcx>, Error> {
+    // The tcx argument ensures that the type won't outlive the type context.
+    with_tables(|tables| {
+        let pointee = pointee.internal(tables, tcx);
+        let param_env = ty::ParamEnv::reveal_all();
+        pointee
+            .ptr_metadata_ty_or_tail(tcx, |ty| {
+                tcx.try_normalize_erasing_regions(param_env, ty).unwrap_or(ty)
+            })
+            .map_err(|tail| {
+                Error::new(format!(
+                    "Cannot determine the pointer metadata of `{pointee}` with tail `{tail}`"
+                ))
+            })
+    })
+}
+
+/// Convert a stable alignment in bytes into its internal Rust compiler counterpart, checking that
+/// it is a power of two supported by the compiler.
+///
+/// # Warning
+///
+/// This function is unstable, and it's behavior may change at any point.
+pub fn internal_align(align: stable_mir::ty::Align) -> Result<rustc_abi::Align, Error> {
+    rustc_abi::Align::from_bytes(align)
+        .map_err(|err| Error::new(format!("Invalid alignment: {err}")))
+}
+
+/// Build the internal range of `size` bytes starting at `offset` of a stable allocation, checking
+/// that the range is within the bounds of the allocation.
+///
+/// # Warning
+///
+/// This function is unstable, and it's behavior may change at any point.
+pub fn internal_alloc_range(
+    alloc: &stable_mir::ty::Allocation,
+    offset: usize,
+    size: usize,
+) -> Result<AllocRange, Error> {
+    let len = alloc.bytes.len();
+    match offset.checked_add(size) {
+        Some(end) if end <= len => {
+            Ok(alloc_range(rustc_abi::Size::from_bytes(offset), rustc_abi::Size::from_bytes(size)))
+        }
+        _ => Err(Error::new(format!(
+            "Range of {size} bytes at offset {offset} is out of bounds. The allocation only has \
+            {len} bytes"
+        ))),
+    }
+}
+
+/// Read the scalar of `size` bytes stored at `offset` of a stable allocation, and convert it into
+/// its internal Rust compiler counterpart.
+///
+/// All the bytes must be initialized. A pointer is read if the range exactly covers a pointer
+/// stored in the allocation, which keeps its provenance. Otherwise, the range must not overlap
+/// any pointer, and the bytes are read as an integer using the target endianness.
+///
+/// # Warning
+///
+/// This function is unstable, and it's behavior may change at any point.
+///
+/// # Panics
+///
+/// This function will panic if StableMIR has not been properly initialized.
+pub fn internal_read_scalar<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    alloc: &stable_mir::ty::Allocation,
+    offset: usize,
+    size: usize,
+) -> Result<Scalar, Error> {
+    // The tcx argument ensures that the scalar won't outlive the type context.
+    with_tables(|tables| {
+        if size == 0 || size > 16 {
+            return Err(Error::new(format!("Cannot read a scalar of {size} bytes")));
+        }
+        internal_alloc_range(alloc, offset, size)?;
+        let range = offset..offset + size;
+        let bytes = alloc.bytes[range.clone()]
+            .iter()
+            .copied()
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| Error::new(format!("Found uninitialized bytes in `{range:?}`")))?;
+        let ptr_size = tcx.data_layout.pointer_size.bytes_usize();
+        let overlap = alloc
+            .provenance
+            .ptrs
+            .iter()
+            .find(|(start, _)| *start < range.end && range.start < *start + ptr_size);
+        let bits = read_target_uint(tcx.data_layout.endian, &bytes).unwrap();
+        match overlap {
+            None => Ok(Scalar::from_uint(bits, rustc_abi::Size::from_bytes(size))),
+            Some((start, prov)) if *start == offset && size == ptr_size => {
+                let alloc_id = prov.0.internal(tables, tcx);
+                let ptr = Pointer::new(alloc_id.into(), rustc_abi::Size::from_bytes(bits));
+                Ok(Scalar::from_pointer(ptr, &tcx))
+            }
+            Some((start, _)) => Err(Error::new(format!(
+                "Range `{range:?}` partially overlaps the pointer stored at offset {start}"
+            ))),
+        }
+    })
+}
+
+/// Build the internal constant of a slice, such as a string literal, whose elements are stored in
+/// the given stable allocation, and whose length is `len` elements of type `elem_ty`.
+///
+/// The elements must fit in the allocation. The pointers stored in the allocation keep their
+/// provenance, and uninitialized bytes remain uninitialized.
+///
+/// # Warning
+///
+/// This function is unstable, and it's behavior may change at any point.
+///
+/// # Panics
+///
+/// This function will panic if StableMIR has not been properly initialized.
+pub fn internal_slice_const<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    alloc: &stable_mir::ty::Allocation,
+    elem_ty: stable_mir::ty::Ty,
+    len: u64,
+) -> Result<ConstValue<'tcx>, Error> {
+    let align = internal_align(alloc.align)?;
+    let elem_ty = internal(tcx, elem_ty);
+    let elem_size = tcx
+        .layout_of(ty::ParamEnv::reveal_all().and(elem_ty))
+        .map_err(|err| Error::new(format!("Failed to compute the layout of `{elem_ty}`: {err}")))?
+        .size
+        .bytes();
+    let size = alloc.bytes.len() as u64;
+    if elem_size.checked_mul(len).map_or(true, |slice_size| slice_size > size) {
+        return Err(Error::new(format!(
+            "A slice of {len} elements of type `{elem_ty}` does not fit in an allocation of {size} \
+            bytes"
+        )));
+    }
+    let bytes = alloc.bytes.iter().map(|byte| byte.unwrap_or_default()).collect::<Vec<_>>();
+    // Writing to the allocation requires it to be mutable. We restore its mutability at the end.
+    let mut internal = Allocation::from_bytes(bytes, align, ty::Mutability::Mut);
+    let ptr_size = tcx.data_layout.pointer_size.bytes_usize();
+    for (offset, _) in &alloc.provenance.ptrs {
+        let ptr = internal_read_scalar(tcx, alloc, *offset, ptr_size)?;
+        let range = internal_alloc_range(alloc, *offset, ptr_size)?;
+        internal.write_scalar(&tcx, range, ptr).map_err(|err| {
+            Error::new(format!("Failed to write the pointer at offset {offset}: {err:?}"))
+        })?;
+    }
+    for (offset, byte) in alloc.bytes.iter().enumerate() {
+        if byte.is_none() {
+            let range = internal_alloc_range(alloc, offset, 1)?;
+            internal.write_uninit(&tcx, range).map_err(|err| {
+                Error::new(format!("Failed to uninitialize the byte at offset {offset}: {err:?}"))
+            })?;
+        }
+    }
+    internal.mutability = alloc.mutability.to_internal();
+    Ok(ConstValue::Slice { data: tcx.mk_const_alloc(internal), meta: len })
+}
+
 impl<'tcx> Index<stable_mir::DefId> for Tables<'tcx> {
     type Output = DefId;
 
@@ -436,3 +1054,28 @@ pub trait RustcInternal {
     type T<'tcx>;
     fn internal<'tcx>(&self, tables: &mut Tables<'_>, tcx: TyCtxt<'tcx>) -> Self::T<'tcx>;
 }
+
+/// Trait used to translate a stable construct to its rustc counterpart when the translation needs
+/// neither the `Tables` nor the type context, e.g., for plain enums like [stable_mir::mir::BinOp].
+///
+/// Constructs that implement this trait also implement [RustcInternal], and can be converted
+/// without initializing StableMIR.
+pub trait IntoInternal {
+    type T;
+    fn to_internal(&self) -> Self::T;
+}
+
+/// Trait used to translate a stable construct to its rustc counterpart while checking that the
+/// construct is valid in the given context.
+///
+/// The context carries the information needed for the validation, such as the definition that
+/// generic arguments are meant for.
+pub trait RustcInternalChecked<C: ?Sized> {
+    type T<'tcx>;
+    fn internal_checked<'tcx>(
+        &self,
+        context: &C,
+        tables: &mut Tables<'_>,
+        tcx: TyCtxt<'tcx>,
+    ) -> Result<Self::T<'tcx>, Error>;
+}
@@ -5,9 +5,9 @@
 use rustc_middle::ty;
 use rustc_target::abi::call::Conv;
 use stable_mir::abi::{
-    AddressSpace, ArgAbi, CallConvention, FieldsShape, FloatLength, FnAbi, IntegerLength, Layout,
-    LayoutShape, PassMode, Primitive, Scalar, TagEncoding, TyAndLayout, ValueAbi, VariantsShape,
-    WrappingRange,
+    AddressSpace, ArgAbi, CallConvention, FieldsShape, FloatLength, FnAbi, IntegerLength,
+    IntegerType, Layout, LayoutShape, PassMode, Primitive, ReprFlags, ReprOptions, Scalar,
+    TagEncoding, TyAndLayout, ValueAbi, VariantsShape, WrappingRange,
 };
 use stable_mir::opaque;
 use stable_mir::target::MachineSize as Size;
@@ -155,9 +155,10 @@ fn stable(&self, tables: &mut Tables<'_>) -> Self::T {
             rustc_abi::FieldsShape::Array { stride, count } => {
                 FieldsShape::Array { stride: stride.stable(tables), count: *count }
             }
-            rustc_abi::FieldsShape::Arbitrary { offsets, .. } => {
-                FieldsShape::Arbitrary { offsets: offsets.iter().as_slice().stable(tables) }
-            }
+            rustc_abi::FieldsShape::Arbitrary { offsets, memory_index } => FieldsShape::Arbitrary {
+                offsets: offsets.iter().as_slice().stable(tables),
+                memory_index: memory_index.iter().copied().collect(),
+            },
         }
     }
 }
@@ -307,3 +308,36 @@ fn stable(&self, _tables: &mut Tables<'_>) -> Self::T {
         WrappingRange { start: self.start, end: self.end }
     }
 }
+
+impl<'tcx> Stable<'tcx> for rustc_abi::ReprOptions {
+    type T = ReprOptions;
+
+    fn stable(&self, tables: &mut Tables<'_>) -> Self::T {
+        ReprOptions {
+            int: self.int.map(|int| int.stable(tables)),
+            align: self.align.map(|align| align.stable(tables)),
+            pack: self.pack.map(|pack| pack.stable(tables)),
+            flags: ReprFlags {
+                is_simd: self.simd(),
+                is_c: self.c(),
+                is_transparent: self.transparent(),
+                is_linear: self.linear(),
+            },
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for rustc_abi::IntegerType {
+    type T = IntegerType;
+
+    fn stable(&self, tables: &mut Tables<'_>) -> Self::T {
+        match self {
+            rustc_abi::IntegerType::Pointer(is_signed) => {
+                IntegerType::Pointer { is_signed: *is_signed }
+            }
+            rustc_abi::IntegerType::Fixed(length, is_signed) => {
+                IntegerType::Fixed { length: length.stable(tables), is_signed: *is_signed }
+            }
+        }
+    }
+}
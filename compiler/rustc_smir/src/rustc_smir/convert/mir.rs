@@ -1,8 +1,8 @@
 //! Conversion of internal Rust compiler `mir` items to stable ones.
 
+use rustc_middle::mir;
 use rustc_middle::mir::interpret::alloc_range;
 use rustc_middle::mir::mono::MonoItem;
-use rustc_middle::{bug, mir};
 use stable_mir::mir::alloc::GlobalAlloc;
 use stable_mir::mir::{ConstOperand, Statement, UserTypeProjection, VarDebugInfoFragment};
 use stable_mir::ty::{Allocation, ConstantKind, MirConst};
@@ -490,13 +490,13 @@ fn stable(&self, _: &mut Tables<'_>) -> Self::T {
         match self {
             BinOp::Add => stable_mir::mir::BinOp::Add,
             BinOp::AddUnchecked => stable_mir::mir::BinOp::AddUnchecked,
-            BinOp::AddWithOverflow => bug!("AddWithOverflow should have been translated already"),
+            BinOp::AddWithOverflow => stable_mir::mir::BinOp::AddWithOverflow,
             BinOp::Sub => stable_mir::mir::BinOp::Sub,
             BinOp::SubUnchecked => stable_mir::mir::BinOp::SubUnchecked,
-            BinOp::SubWithOverflow => bug!("AddWithOverflow should have been translated already"),
+            BinOp::SubWithOverflow => stable_mir::mir::BinOp::SubWithOverflow,
             BinOp::Mul => stable_mir::mir::BinOp::Mul,
             BinOp::MulUnchecked => stable_mir::mir::BinOp::MulUnchecked,
-            BinOp::MulWithOverflow => bug!("AddWithOverflow should have been translated already"),
+            BinOp::MulWithOverflow => stable_mir::mir::BinOp::MulWithOverflow,
             BinOp::Div => stable_mir::mir::BinOp::Div,
             BinOp::Rem => stable_mir::mir::BinOp::Rem,
             BinOp::BitXor => stable_mir::mir::BinOp::BitXor,
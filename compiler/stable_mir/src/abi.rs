@@ -152,6 +152,9 @@ pub enum FieldsShape {
         /// I.e.: It follows the same order as [crate::ty::VariantDef::fields()].
         /// This vector does not go in increasing order.
         offsets: Vec<Size>,
+        /// Maps source order field indices to memory order indices,
+        /// depending on how the fields were reordered (if at all).
+        memory_index: Vec<u32>,
     },
 }
 
@@ -352,6 +355,37 @@ pub fn bits(self) -> usize {
     }
 }
 
+/// The representation options of an ADT, as provided by the user with `#[repr]` attributes.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize)]
+pub struct ReprOptions {
+    /// The integer type of `#[repr(<int>)]`, if any.
+    pub int: Option<IntegerType>,
+    /// The alignment in bytes of `#[repr(align(N))]`, if any.
+    pub align: Option<Align>,
+    /// The packing in bytes of `#[repr(packed(N))]`, if any.
+    pub pack: Option<Align>,
+    pub flags: ReprFlags,
+}
+
+/// The flags of the representation options of an ADT.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize)]
+pub struct ReprFlags {
+    pub is_simd: bool,
+    pub is_c: bool,
+    pub is_transparent: bool,
+    /// Whether the fields must be laid out in declaration order.
+    pub is_linear: bool,
+}
+
+/// The integer type used to represent the discriminant of an ADT.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize)]
+pub enum IntegerType {
+    /// Pointer-sized integer type, i.e. `isize` and `usize`.
+    Pointer { is_signed: bool },
+    /// Fixed-sized integer type, e.g. `i8`, `u32`, `i128`.
+    Fixed { length: IntegerLength, is_signed: bool },
+}
+
 /// An identifier that specifies the address space that some operation
 /// should operate on. Special address spaces have an effect on code generation,
 /// depending on the target and the address spaces it implements.
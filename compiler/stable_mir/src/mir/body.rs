@@ -314,10 +314,13 @@ pub fn description(&self) -> Result<&'static str, Error> {
 pub enum BinOp {
     Add,
     AddUnchecked,
+    AddWithOverflow,
     Sub,
     SubUnchecked,
+    SubWithOverflow,
     Mul,
     MulUnchecked,
+    MulWithOverflow,
     Div,
     Rem,
     BitXor,
@@ -479,6 +482,8 @@ pub enum Rvalue {
     ///   truncated as needed.
     /// * The `Bit*` operations accept signed integers, unsigned integers, or bools with matching
     ///   types and return a value of that type.
+    /// * The `FooWithOverflow` are like the `Foo`, but returning `(T, bool)` instead of just `T`,
+    ///   where the `bool` is true if the result is not equal to the infinite-precision result.
     /// * The remaining operations accept signed integers, unsigned integers, or floats with
     ///   matching types and return a value of that type.
     BinaryOp(BinOp, Operand, Operand),
@@ -5,7 +5,7 @@
 
 use super::mir::{Body, Mutability, Safety};
 use super::{with, DefId, Error, Symbol};
-use crate::abi::{FnAbi, Layout};
+use crate::abi::{FnAbi, Layout, ReprOptions};
 use crate::crate_def::{CrateDef, CrateDefType};
 use crate::mir::alloc::{read_target_int, read_target_uint, AllocId};
 use crate::mir::mono::StaticDef;
@@ -779,6 +779,11 @@ pub fn is_simd(&self) -> bool {
         with(|cx| cx.adt_is_simd(*self))
     }
 
+    /// Retrieve the representation options of this ADT.
+    pub fn repr(&self) -> ReprOptions {
+        with(|cx| cx.adt_repr(*self))
+    }
+
     /// The number of variants in this ADT.
     pub fn num_variants(&self) -> usize {
         with(|cx| cx.adt_variants_len(*self))
@@ -1416,6 +1421,23 @@ pub struct GenericPredicates {
     pub predicates: Vec<(PredicateKind, Span)>,
 }
 
+/// The environment used for trait selection and normalization.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct ParamEnv {
+    /// The clauses that are assumed to hold in this environment.
+    pub caller_bounds: Vec<ClauseKind>,
+    pub reveal: Reveal,
+}
+
+/// Whether opaque types and specializable associated items can be revealed.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize)]
+pub enum Reveal {
+    /// Only reveal what is visible to the user, e.g. during type checking.
+    UserFacing,
+    /// Reveal everything, e.g. during codegen.
+    All,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq, Serialize)]
 pub enum PredicateKind {
     Clause(ClauseKind),
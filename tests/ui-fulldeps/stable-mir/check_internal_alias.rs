@@ -0,0 +1,94 @@
+//@ run-pass
+//! Test that alias types can be converted to their internal representation, including the
+//! return position `impl Trait` of trait methods.
+
+//@ ignore-stage1
+//@ ignore-cross-compile
+//@ ignore-remote
+//@ ignore-windows-gnu mingw has troubles with linking https://github.com/rust-lang/rust/pull/116837
+//@ edition: 2021
+
+#![feature(rustc_private)]
+#![feature(assert_matches)]
+#![feature(control_flow_enum)]
+
+#[macro_use]
+extern crate rustc_smir;
+extern crate rustc_driver;
+extern crate rustc_interface;
+extern crate rustc_middle;
+extern crate stable_mir;
+
+use rustc_middle::ty::TyCtxt;
+use rustc_smir::rustc_internal;
+use stable_mir::mir::Body;
+use stable_mir::ty::{AliasKind, AliasTy, Ty, TyKind};
+use stable_mir::CrateItems;
+use std::io::Write;
+use std::ops::ControlFlow;
+
+const CRATE_NAME: &str = "input";
+
+fn test_alias(tcx: TyCtxt<'_>) -> ControlFlow<()> {
+    let items = stable_mir::all_local_items();
+    let (ty, alias) = find_projection(&get_item(&items, "first").unwrap().body());
+
+    let internal = rustc_internal::internal_checked(tcx, &alias, &AliasKind::Projection).unwrap();
+    assert!(tcx.opt_rpitit_info(internal.def_id).is_some());
+    assert_eq!(internal.to_ty(tcx), rustc_internal::internal(tcx, ty));
+    assert_eq!(rustc_internal::stable(internal), alias);
+
+    // The synthesized associated type is not an opaque type.
+    let error = rustc_internal::internal_checked(tcx, &alias, &AliasKind::Opaque).unwrap_err();
+    assert!(error.to_string().contains("alias of kind `Opaque`"), "Unexpected error: {error}");
+    ControlFlow::Continue(())
+}
+
+/// Find the projection stored in the locals of the given body.
+fn find_projection(body: &Body) -> (Ty, AliasTy) {
+    body.locals()
+        .iter()
+        .find_map(|local| match local.ty.kind() {
+            TyKind::Alias(AliasKind::Projection, alias) => Some((local.ty, alias)),
+            _ => None,
+        })
+        .unwrap()
+}
+
+fn get_item<'a>(items: &'a CrateItems, name: &str) -> Option<&'a stable_mir::CrateItem> {
+    items.iter().find(|crate_item| crate_item.name() == name)
+}
+
+/// This test will generate and analyze a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// Then it will create a `StableMir` using custom arguments and then
+/// it will run the compiler.
+fn main() {
+    let path = "internal_alias_input.rs";
+    generate_input(&path).unwrap();
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.to_string(),
+    ];
+    run_with_tcx!(args, test_alias).unwrap();
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+    pub trait Numbers {{
+        fn numbers(&self) -> impl Iterator<Item = u8>;
+    }}
+
+    pub fn first<T: Numbers>(source: &T) -> Option<u8> {{
+        source.numbers().next()
+    }}
+    "#
+    )?;
+    Ok(())
+}
@@ -0,0 +1,127 @@
+//@ run-pass
+//! Test that generic arguments are validated against the generics of the definition they are
+//! meant for when converting them to their internal representation.
+
+//@ ignore-stage1
+//@ ignore-cross-compile
+//@ ignore-remote
+//@ ignore-windows-gnu mingw has troubles with linking https://github.com/rust-lang/rust/pull/116837
+//@ edition: 2021
+
+#![feature(rustc_private)]
+#![feature(assert_matches)]
+#![feature(control_flow_enum)]
+
+#[macro_use]
+extern crate rustc_smir;
+extern crate rustc_driver;
+extern crate rustc_interface;
+extern crate rustc_middle;
+extern crate stable_mir;
+
+use rustc_middle::ty::TyCtxt;
+use rustc_smir::rustc_internal;
+use stable_mir::crate_def::CrateDef;
+use stable_mir::ty::{GenericArgs, RigidTy, TyKind};
+use stable_mir::CrateItems;
+use std::io::Write;
+use std::ops::ControlFlow;
+
+const CRATE_NAME: &str = "input";
+
+fn test_generic_args(tcx: TyCtxt<'_>) -> ControlFlow<()> {
+    let items = stable_mir::all_local_items();
+    let wrap = get_item(&items, "wrap").unwrap();
+    let body = wrap.body();
+    let TyKind::RigidTy(RigidTy::Adt(def, args)) = body.locals()[1].ty.kind() else {
+        unreachable!("Expected `Wrapper` type, but found: {:?}", body.locals()[1].ty)
+    };
+
+    let checked = rustc_internal::internal_checked(tcx, &args, &def.def_id()).unwrap();
+    assert_eq!(checked, rustc_internal::internal(tcx, &args));
+
+    // The lifetime argument keeps the early bound `'a` of `wrap` instead of being erased.
+    let region = checked.region_at(0);
+    assert!(!region.is_erased());
+    let rustc_middle::ty::ReEarlyParam(param) = region.kind() else {
+        unreachable!("Expected an early bound region, but found: {region:?}")
+    };
+    assert_eq!(param.name.as_str(), "'a");
+
+    // Put the type argument where the lifetime is expected.
+    let swapped = GenericArgs(args.0.iter().rev().cloned().collect());
+    let error = rustc_internal::internal_checked(tcx, &swapped, &def.def_id()).unwrap_err();
+    assert!(error.to_string().contains("Expected a lifetime"), "Unexpected error: {error}");
+
+    // Drop the last argument.
+    let missing = GenericArgs(args.0[..1].to_vec());
+    let error = rustc_internal::internal_checked(tcx, &missing, &def.def_id()).unwrap_err();
+    assert!(
+        error.to_string().contains("Expected 2 generic arguments"),
+        "Unexpected error: {error}"
+    );
+
+    // `T` has no default.
+    let error = rustc_internal::internal_args_with_defaults(tcx, def.def_id(), &missing);
+    let error = error.unwrap_err();
+    assert!(error.to_string().contains("which has no default"), "Unexpected error: {error}");
+
+    check_defaults(tcx, get_item(&items, "fill").unwrap());
+    ControlFlow::Continue(())
+}
+
+/// Check that omitted trailing arguments are replaced by their defaults.
+fn check_defaults(tcx: TyCtxt<'_>, item: &stable_mir::CrateItem) {
+    let ty = item.body().locals()[1].ty;
+    let TyKind::RigidTy(RigidTy::Adt(def, args)) = ty.kind() else {
+        unreachable!("Expected `Buffer` type, but found: {ty:?}")
+    };
+    // `Buffer<u8>`
+    let omitted = GenericArgs(args.0[..1].to_vec());
+    let internal = rustc_internal::internal_args_with_defaults(tcx, def.def_id(), &omitted);
+    let internal = internal.unwrap();
+    assert_eq!(internal, rustc_internal::internal(tcx, &args));
+    assert_eq!(internal.const_at(1).try_to_target_usize(tcx), Some(4));
+}
+
+fn get_item<'a>(items: &'a CrateItems, name: &str) -> Option<&'a stable_mir::CrateItem> {
+    items.iter().find(|crate_item| crate_item.name() == name)
+}
+
+/// This test will generate and analyze a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// Then it will create a `StableMir` using custom arguments and then
+/// it will run the compiler.
+fn main() {
+    let path = "internal_args_input.rs";
+    generate_input(&path).unwrap();
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.to_string(),
+    ];
+    run_with_tcx!(args, test_generic_args).unwrap();
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+    pub struct Wrapper<'a, T>(&'a T);
+
+    pub fn wrap<'a>(wrapper: Wrapper<'a, u8>) -> Wrapper<'a, u8> {{
+        wrapper
+    }}
+
+    pub struct Buffer<T, const N: usize = 4>([T; N]);
+
+    pub fn fill(buffer: Buffer<u8>) -> Buffer<u8> {{
+        buffer
+    }}
+    "#
+    )?;
+    Ok(())
+}
@@ -0,0 +1,96 @@
+//@ run-pass
+//! Test that the associated items of a stable trait can be retrieved in their internal
+//! representation.
+
+//@ ignore-stage1
+//@ ignore-cross-compile
+//@ ignore-remote
+//@ ignore-windows-gnu mingw has troubles with linking https://github.com/rust-lang/rust/pull/116837
+//@ edition: 2021
+
+#![feature(rustc_private)]
+#![feature(assert_matches)]
+#![feature(control_flow_enum)]
+
+#[macro_use]
+extern crate rustc_smir;
+extern crate rustc_driver;
+extern crate rustc_interface;
+extern crate rustc_middle;
+extern crate rustc_span;
+extern crate stable_mir;
+
+use rustc_middle::ty::{AssocKind, TyCtxt};
+use rustc_smir::rustc_internal;
+use rustc_span::Symbol;
+use stable_mir::crate_def::CrateDef;
+use stable_mir::ty::{ExistentialPredicate, RigidTy, TraitDef, TyKind};
+use stable_mir::CrateItems;
+use std::io::Write;
+use std::ops::ControlFlow;
+
+const CRATE_NAME: &str = "input";
+
+fn test_assoc_items(tcx: TyCtxt<'_>) -> ControlFlow<()> {
+    let items = stable_mir::all_local_items();
+    let iter = get_item(&items, "iter").unwrap();
+    let iterator = principal(&iter.body().locals()[1].ty);
+    assert!(iterator.name().ends_with("Iterator"), "Unexpected trait: {}", iterator.name());
+
+    let assoc_items = rustc_internal::internal_assoc_items(tcx, iterator).unwrap();
+    let next = assoc_items.filter_by_name_unhygienic(Symbol::intern("next")).next().unwrap();
+    assert_eq!(next.kind, AssocKind::Fn);
+    assert!(next.fn_has_self_parameter);
+    let item = assoc_items.filter_by_name_unhygienic(Symbol::intern("Item")).next().unwrap();
+    assert_eq!(item.kind, AssocKind::Type);
+    assert_eq!(tcx.parent(item.def_id), rustc_internal::internal(tcx, iterator.def_id()));
+
+    let error = rustc_internal::internal_assoc_items(tcx, TraitDef(iter.def_id())).unwrap_err();
+    assert!(error.to_string().contains("Expected a trait"), "Unexpected error: {error}");
+    ControlFlow::Continue(())
+}
+
+/// Get the principal trait of the `dyn` type behind the given reference.
+fn principal(ref_ty: &stable_mir::ty::Ty) -> TraitDef {
+    let TyKind::RigidTy(RigidTy::Ref(_, dyn_ty, _)) = ref_ty.kind() else { unreachable!() };
+    let TyKind::RigidTy(RigidTy::Dynamic(predicates, _, _)) = dyn_ty.kind() else { unreachable!() };
+    predicates
+        .iter()
+        .find_map(|pred| match &pred.value {
+            ExistentialPredicate::Trait(trait_ref) => Some(trait_ref.def_id),
+            _ => None,
+        })
+        .unwrap()
+}
+
+fn get_item<'a>(items: &'a CrateItems, name: &str) -> Option<&'a stable_mir::CrateItem> {
+    items.iter().find(|crate_item| crate_item.name() == name)
+}
+
+/// This test will generate and analyze a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// Then it will create a `StableMir` using custom arguments and then
+/// it will run the compiler.
+fn main() {
+    let path = "internal_assoc_items_input.rs";
+    generate_input(&path).unwrap();
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.to_string(),
+    ];
+    run_with_tcx!(args, test_assoc_items).unwrap();
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+    pub fn iter(_iter: &dyn Iterator<Item = u8>) {{}}
+    "#
+    )?;
+    Ok(())
+}
@@ -0,0 +1,90 @@
+//@ run-pass
+//! Test that the auto traits of a `dyn` type are sorted when converting the type to its internal
+//! representation, so that their order does not matter.
+
+//@ ignore-stage1
+//@ ignore-cross-compile
+//@ ignore-remote
+//@ ignore-windows-gnu mingw has troubles with linking https://github.com/rust-lang/rust/pull/116837
+//@ edition: 2021
+
+#![feature(rustc_private)]
+#![feature(assert_matches)]
+#![feature(control_flow_enum)]
+
+#[macro_use]
+extern crate rustc_smir;
+extern crate rustc_driver;
+extern crate rustc_interface;
+extern crate rustc_middle;
+extern crate stable_mir;
+
+use rustc_middle::ty::TyCtxt;
+use rustc_smir::rustc_internal;
+use stable_mir::ty::{ExistentialPredicate, RigidTy, TyKind};
+use stable_mir::CrateItems;
+use std::io::Write;
+use std::ops::ControlFlow;
+
+const CRATE_NAME: &str = "input";
+
+fn test_auto_traits(tcx: TyCtxt<'_>) -> ControlFlow<()> {
+    let items = stable_mir::all_local_items();
+    let body = get_item(&items, "show").unwrap().body();
+    let TyKind::RigidTy(RigidTy::Ref(_, dyn_ty, _)) = body.locals()[1].ty.kind() else {
+        unreachable!()
+    };
+    let kind = dyn_ty.kind();
+    let TyKind::RigidTy(RigidTy::Dynamic(predicates, region, dyn_kind)) = &kind else {
+        unreachable!("Expected a `dyn` type, but found: {kind:?}")
+    };
+    let auto_traits = predicates
+        .iter()
+        .filter(|pred| matches!(pred.value, ExistentialPredicate::AutoTrait(_)))
+        .count();
+    assert_eq!(auto_traits, 2);
+
+    // Swap `Send` and `Sync`, which must produce the same type.
+    let mut reordered = predicates.clone();
+    let len = reordered.len();
+    reordered.swap(len - 2, len - 1);
+    assert_ne!(&reordered, predicates);
+    let reordered = TyKind::RigidTy(RigidTy::Dynamic(reordered, region.clone(), dyn_kind.clone()));
+
+    let internal = rustc_internal::internal(tcx, &kind);
+    assert_eq!(internal, rustc_internal::internal(tcx, dyn_ty));
+    assert_eq!(internal, rustc_internal::internal(tcx, &reordered));
+    ControlFlow::Continue(())
+}
+
+fn get_item<'a>(items: &'a CrateItems, name: &str) -> Option<&'a stable_mir::CrateItem> {
+    items.iter().find(|crate_item| crate_item.name() == name)
+}
+
+/// This test will generate and analyze a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// Then it will create a `StableMir` using custom arguments and then
+/// it will run the compiler.
+fn main() {
+    let path = "internal_auto_traits_input.rs";
+    generate_input(&path).unwrap();
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.to_string(),
+    ];
+    run_with_tcx!(args, test_auto_traits).unwrap();
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+    pub fn show(_value: &(dyn std::fmt::Debug + Send + Sync)) {{}}
+    "#
+    )?;
+    Ok(())
+}
@@ -0,0 +1,296 @@
+//@ run-pass
+//! Test that MIR constructs are validated against the body they belong to when converting them
+//! to their internal representation.
+
+//@ ignore-stage1
+//@ ignore-cross-compile
+//@ ignore-remote
+//@ ignore-windows-gnu mingw has troubles with linking https://github.com/rust-lang/rust/pull/116837
+//@ edition: 2021
+
+#![feature(rustc_private)]
+#![feature(assert_matches)]
+#![feature(control_flow_enum)]
+
+#[macro_use]
+extern crate rustc_smir;
+extern crate rustc_driver;
+extern crate rustc_interface;
+extern crate rustc_middle;
+extern crate stable_mir;
+
+use rustc_middle::ty::TyCtxt;
+use rustc_smir::rustc_internal;
+use stable_mir::crate_def::CrateDef;
+use stable_mir::mir::mono::{Instance, InstanceKind};
+use stable_mir::mir::{
+    BinOp, Body, Place, ProjectionElem, Rvalue, StatementKind, SwitchTargets, TerminatorKind,
+};
+use stable_mir::ty::{RigidTy, Ty, TyKind, UintTy};
+use stable_mir::CrateItems;
+use std::assert_matches::assert_matches;
+use std::io::Write;
+use std::ops::ControlFlow;
+
+const CRATE_NAME: &str = "input";
+
+fn test_body_checks(tcx: TyCtxt<'_>) -> ControlFlow<()> {
+    let items = stable_mir::all_local_items();
+    let body = get_item(&items, "add").unwrap().body();
+    check_place(tcx, &body);
+    check_local(tcx, &get_item(&items, "pick").unwrap().body());
+    check_index(tcx, &get_item(&items, "pick").unwrap().body());
+    check_overflow_binop(tcx, &get_item(&items, "checked_add").unwrap().body());
+    check_switch_targets(tcx, &get_item(&items, "select").unwrap().body());
+    check_discriminant(tcx, &get_item(&items, "level").unwrap().body());
+    check_debug_info(tcx, get_item(&items, "offset").unwrap());
+    check_bodyless_scope(tcx, &get_item(&items, "dyn_call").unwrap().body());
+    ControlFlow::Continue(())
+}
+
+/// Check that a place can only refer to locals declared in the body.
+fn check_place(tcx: TyCtxt<'_>, body: &Body) {
+    let last = Place::from(body.locals().len() - 1);
+    let internal = rustc_internal::internal_checked(tcx, &last, body).unwrap();
+    assert_eq!(internal.local.as_usize(), last.local);
+
+    let out_of_range = Place::from(body.locals().len());
+    let error = rustc_internal::internal_checked(tcx, &out_of_range, body).unwrap_err();
+    assert!(error.to_string().contains("is out of range"), "Unexpected error: {error}");
+}
+
+/// Check that a local of the body is converted together with its type.
+fn check_local(tcx: TyCtxt<'_>, body: &Body) {
+    let (local, ty) = rustc_internal::internal_local(tcx, 2, body).unwrap();
+    assert_eq!(local.as_usize(), 2);
+    assert_eq!(ty, rustc_internal::internal(tcx, body.locals()[2].ty));
+    assert_eq!(ty, tcx.types.usize);
+
+    let error = rustc_internal::internal_local(tcx, body.locals().len(), body).unwrap_err();
+    assert!(error.to_string().contains("is out of range"), "Unexpected error: {error}");
+}
+
+/// Check that a place can only be indexed by a `usize` local of the body.
+fn check_index(tcx: TyCtxt<'_>, body: &Body) {
+    // `values[idx]`
+    let indexed = Place { local: 1, projection: vec![ProjectionElem::Index(2)] };
+    let internal = rustc_internal::internal_checked(tcx, &indexed, body).unwrap();
+    assert_eq!(internal, rustc_internal::internal(tcx, &indexed));
+
+    // The return local has type `u8`.
+    let wrong_type = Place { local: 1, projection: vec![ProjectionElem::Index(0)] };
+    let error = rustc_internal::internal_checked(tcx, &wrong_type, body).unwrap_err();
+    assert!(error.to_string().contains("to have type `usize`"), "Unexpected error: {error}");
+
+    let out_of_range =
+        Place { local: 1, projection: vec![ProjectionElem::Index(body.locals().len())] };
+    let error = rustc_internal::internal_checked(tcx, &out_of_range, body).unwrap_err();
+    assert!(error.to_string().contains("is out of range"), "Unexpected error: {error}");
+}
+
+/// Check that an overflow checked addition round trips as `AddWithOverflow`, and keeps its
+/// `(T, bool)` type.
+fn check_overflow_binop(tcx: TyCtxt<'_>, body: &Body) {
+    let rvalue = body
+        .blocks
+        .iter()
+        .flat_map(|block| &block.statements)
+        .find_map(|stmt| match &stmt.kind {
+            StatementKind::Assign(_, rvalue @ Rvalue::CheckedBinaryOp(BinOp::Add, ..)) => {
+                Some(rvalue)
+            }
+            _ => None,
+        })
+        .unwrap();
+    let Rvalue::CheckedBinaryOp(op, lhs, rhs) = rvalue else { unreachable!() };
+
+    let internal_op = rustc_internal::internal(tcx, op).wrapping_to_overflowing().unwrap();
+    assert_eq!(internal_op, rustc_middle::mir::BinOp::AddWithOverflow);
+    assert_eq!(rustc_internal::stable(internal_op), BinOp::AddWithOverflow);
+    assert_eq!(rustc_internal::internal(tcx, BinOp::AddWithOverflow), internal_op);
+
+    let lhs_ty = lhs.ty(body.locals()).unwrap();
+    let rhs_ty = rhs.ty(body.locals()).unwrap();
+    let internal_ty = internal_op.ty(
+        tcx,
+        rustc_internal::internal(tcx, lhs_ty),
+        rustc_internal::internal(tcx, rhs_ty),
+    );
+    let rvalue_ty = rvalue.ty(body.locals()).unwrap();
+    assert_eq!(internal_ty, rustc_internal::internal(tcx, rvalue_ty));
+    assert_eq!(BinOp::AddWithOverflow.ty(lhs_ty, rhs_ty), rvalue_ty);
+    assert_eq!(internal_ty, rustc_middle::ty::Ty::new_tup(tcx, &[tcx.types.u8, tcx.types.bool]));
+}
+
+/// Check that switch targets can only refer to blocks of the body, and that their values must fit
+/// the discriminant type.
+fn check_switch_targets(tcx: TyCtxt<'_>, body: &Body) {
+    let (discr, targets) = body
+        .blocks
+        .iter()
+        .find_map(|block| match &block.terminator.kind {
+            TerminatorKind::SwitchInt { discr, targets } => Some((discr, targets)),
+            _ => None,
+        })
+        .unwrap();
+    let internal = rustc_internal::internal_checked(tcx, targets, body).unwrap();
+    assert_eq!(internal, rustc_internal::internal(tcx, targets));
+
+    let out_of_range = SwitchTargets::new(vec![(0, body.blocks.len())], targets.otherwise());
+    let error = rustc_internal::internal_checked(tcx, &out_of_range, body).unwrap_err();
+    assert!(error.to_string().contains("is out of range"), "Unexpected error: {error}");
+
+    // The branch values must fit the type of the discriminant, which is `u8` here.
+    let discr_ty = discr.ty(body.locals()).unwrap();
+    assert_eq!(discr_ty, Ty::unsigned_ty(UintTy::U8));
+    let internal = rustc_internal::internal_checked(tcx, targets, &discr_ty).unwrap();
+    assert_eq!(internal, rustc_internal::internal(tcx, targets));
+
+    let too_large = SwitchTargets::new(vec![(256, targets.otherwise())], targets.otherwise());
+    let error = rustc_internal::internal_checked(tcx, &too_large, &discr_ty).unwrap_err();
+    assert!(error.to_string().contains("Value `256` is not a valid"), "Unexpected error: {error}");
+
+    let not_bool = SwitchTargets::new(vec![(5, targets.otherwise())], targets.otherwise());
+    let error = rustc_internal::internal_checked(tcx, &not_bool, &Ty::bool_ty()).unwrap_err();
+    assert!(error.to_string().contains("Value `5` is not a valid"), "Unexpected error: {error}");
+}
+
+/// Check that the discriminant of a `#[repr(i16)]` enum is typed as `i16`.
+fn check_discriminant(tcx: TyCtxt<'_>, body: &Body) {
+    let (dest, place) = body
+        .blocks
+        .iter()
+        .flat_map(|block| &block.statements)
+        .find_map(|stmt| match &stmt.kind {
+            StatementKind::Assign(dest, Rvalue::Discriminant(place)) => Some((dest, place)),
+            _ => None,
+        })
+        .unwrap();
+    let discr_ty = rustc_internal::internal_discriminant_ty(tcx, place, body).unwrap();
+    assert_eq!(discr_ty, tcx.types.i16);
+    let dest_ty = dest.ty(body.locals()).unwrap();
+    assert_eq!(discr_ty, rustc_internal::internal(tcx, dest_ty));
+
+    let out_of_range = Place::from(body.locals().len());
+    let error = rustc_internal::internal_discriminant_ty(tcx, &out_of_range, body).unwrap_err();
+    assert!(error.to_string().contains("is out of range"), "Unexpected error: {error}");
+}
+
+/// Check that each debug information entry converts to the one of the internal body.
+fn check_debug_info(tcx: TyCtxt<'_>, item: &stable_mir::CrateItem) {
+    let body = item.body();
+    let def_id = rustc_internal::internal(tcx, item.def_id());
+    let expected = &tcx.optimized_mir(def_id).var_debug_info;
+    assert_eq!(body.var_debug_info.len(), expected.len());
+    for (info, expected) in body.var_debug_info.iter().zip(expected) {
+        let internal = rustc_internal::internal(tcx, info);
+        assert_eq!(internal.name, expected.name);
+        assert_eq!(internal.source_info, expected.source_info);
+        assert_eq!(internal.argument_index, expected.argument_index);
+        assert_eq!(format!("{:?}", internal.value), format!("{:?}", expected.value));
+    }
+
+    let x = body.var_debug_info.iter().find(|info| info.name == "x").unwrap();
+    assert_eq!(rustc_internal::internal(tcx, x).name.as_str(), "x");
+
+    // `x` is declared in a scope nested in the scope of the function.
+    let instance = Instance::try_from(*item).unwrap();
+    let scope = x.source_info.scope;
+    assert_ne!(scope, 0);
+    let internal = rustc_internal::internal_source_scope(tcx, scope, instance).unwrap();
+    assert_eq!(internal, rustc_internal::internal(tcx, &x.source_info).scope);
+    let error = rustc_internal::internal_source_scope(tcx, u32::MAX >> 1, instance).unwrap_err();
+    assert!(error.to_string().contains("is out of range"), "Unexpected error: {error}");
+    let error = rustc_internal::internal_source_scope(tcx, u32::MAX, instance).unwrap_err();
+    assert!(error.to_string().contains("is out of range"), "Unexpected error: {error}");
+}
+
+/// Check that source scopes of an instance without a body are rejected.
+fn check_bodyless_scope(tcx: TyCtxt<'_>, body: &Body) {
+    let instance = body
+        .blocks
+        .iter()
+        .find_map(|bb| match &bb.terminator.kind {
+            TerminatorKind::Call { func, .. } => {
+                let TyKind::RigidTy(RigidTy::FnDef(def, args)) =
+                    func.ty(body.locals()).unwrap().kind()
+                else {
+                    unreachable!()
+                };
+                Some(Instance::resolve(def, &args).unwrap())
+            }
+            _ => None,
+        })
+        .unwrap();
+    assert_matches!(instance.kind, InstanceKind::Virtual { .. });
+    let error = rustc_internal::internal_source_scope(tcx, 0, instance).unwrap_err();
+    assert!(error.to_string().contains("has no body"), "Unexpected error: {error}");
+}
+
+fn get_item<'a>(items: &'a CrateItems, name: &str) -> Option<&'a stable_mir::CrateItem> {
+    items.iter().find(|crate_item| crate_item.name() == name)
+}
+
+/// This test will generate and analyze a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// Then it will create a `StableMir` using custom arguments and then
+/// it will run the compiler.
+fn main() {
+    let path = "internal_body_input.rs";
+    generate_input(&path).unwrap();
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.to_string(),
+    ];
+    run_with_tcx!(args, test_body_checks).unwrap();
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+    pub fn add(lhs: u8, rhs: u8) -> u8 {{
+        lhs.wrapping_add(rhs)
+    }}
+
+    pub fn checked_add(lhs: u8, rhs: u8) -> u8 {{
+        lhs + rhs
+    }}
+
+    pub fn select(value: u8) -> u8 {{
+        match value {{
+            0 => 10,
+            _ => 20,
+        }}
+    }}
+
+    #[repr(i16)]
+    pub enum Level {{
+        Low = -1,
+        High = 1,
+    }}
+
+    pub fn level(level: Level) -> bool {{
+        matches!(level, Level::High)
+    }}
+
+    pub fn pick(values: [u8; 4], idx: usize) -> u8 {{
+        values[idx]
+    }}
+
+    pub fn offset(value: u8) -> u8 {{
+        let x = 1;
+        value.wrapping_add(x)
+    }}
+
+    pub fn dyn_call(f: &dyn Fn() -> u8) -> u8 {{
+        f()
+    }}
+    "#
+    )?;
+    Ok(())
+}
@@ -0,0 +1,122 @@
+//@ run-pass
+//! Test that the type parameters bound by a binder are checked against the item that declares
+//! them, and that types bound by a binder can be built from their parts.
+
+//@ ignore-stage1
+//@ ignore-cross-compile
+//@ ignore-remote
+//@ ignore-windows-gnu mingw has troubles with linking https://github.com/rust-lang/rust/pull/116837
+//@ edition: 2021
+
+#![feature(rustc_private)]
+#![feature(assert_matches)]
+#![feature(control_flow_enum)]
+
+#[macro_use]
+extern crate rustc_smir;
+extern crate rustc_driver;
+extern crate rustc_interface;
+extern crate rustc_middle;
+extern crate stable_mir;
+
+use rustc_middle::ty::TyCtxt;
+use rustc_smir::rustc_internal;
+use stable_mir::crate_def::CrateDef;
+use stable_mir::ty::{BoundTy, BoundTyKind, BoundVariableKind, ParamDef, TyKind};
+use stable_mir::CrateItems;
+use std::io::Write;
+use std::ops::ControlFlow;
+
+const CRATE_NAME: &str = "input";
+
+fn test_bound_ty(tcx: TyCtxt<'_>) -> ControlFlow<()> {
+    let items = stable_mir::all_local_items();
+    let item = get_item(&items, "bounded").unwrap().def_id();
+
+    // `for<T> T: Holder<U>`
+    let internal_item = rustc_internal::internal(tcx, item);
+    let clause = tcx
+        .predicates_of(internal_item)
+        .predicates
+        .iter()
+        .map(|(clause, _)| clause.kind())
+        .find(|clause| !clause.bound_vars().is_empty())
+        .unwrap();
+    let bound_var = clause.bound_vars()[0];
+    let stable = rustc_internal::stable(bound_var);
+    let internal = rustc_internal::internal_checked(tcx, &stable, &item).unwrap();
+    assert_eq!(internal, bound_var);
+    let BoundVariableKind::Ty(BoundTyKind::Param(def, name)) = &stable else {
+        unreachable!("Expected a bound type parameter, but found: {stable:?}")
+    };
+    assert_eq!(name, "T");
+
+    // `T` is bound by the innermost binder, i.e., the binder of the clause.
+    let bound_ty = BoundTy { var: 0, kind: BoundTyKind::Param(*def, name.clone()) };
+    let self_ty = rustc_internal::internal(tcx, TyKind::Bound(0, bound_ty));
+    let rustc_middle::ty::ClauseKind::Trait(predicate) = clause.skip_binder() else {
+        unreachable!("Expected a trait clause, but found: {clause:?}")
+    };
+    assert_eq!(self_ty, predicate.self_ty());
+    let rustc_middle::ty::Bound(debruijn, _) = self_ty.kind() else { unreachable!() };
+    assert_eq!(*debruijn, rustc_middle::ty::INNERMOST);
+
+    // The name must match the definition.
+    let renamed = BoundVariableKind::Ty(BoundTyKind::Param(*def, "U".to_string()));
+    let error = rustc_internal::internal_checked(tcx, &renamed, &item).unwrap_err();
+    assert!(error.to_string().contains("Expected type parameter `T`"), "Unexpected error: {error}");
+
+    // The parameter must be declared by the given item.
+    let other = get_item(&items, "unbounded").unwrap().def_id();
+    let error = rustc_internal::internal_checked(tcx, &stable, &other).unwrap_err();
+    assert!(error.to_string().contains("is not declared by"), "Unexpected error: {error}");
+
+    // The definition must be a type parameter.
+    let not_param = BoundVariableKind::Ty(BoundTyKind::Param(ParamDef(item), "T".to_string()));
+    let error = rustc_internal::internal_checked(tcx, &not_param, &item).unwrap_err();
+    assert!(error.to_string().contains("Expected a type parameter"), "Unexpected error: {error}");
+    ControlFlow::Continue(())
+}
+
+fn get_item<'a>(items: &'a CrateItems, name: &str) -> Option<&'a stable_mir::CrateItem> {
+    items.iter().find(|crate_item| crate_item.name() == name)
+}
+
+/// This test will generate and analyze a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// Then it will create a `StableMir` using custom arguments and then
+/// it will run the compiler.
+fn main() {
+    let path = "internal_bound_ty_input.rs";
+    generate_input(&path).unwrap();
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.to_string(),
+    ];
+    run_with_tcx!(args, test_bound_ty).unwrap();
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+    #![feature(non_lifetime_binders)]
+    #![allow(incomplete_features)]
+
+    pub trait Holder<U> {{}}
+
+    pub fn bounded<U>()
+    where
+        for<T> T: Holder<U>,
+    {{
+    }}
+
+    pub fn unbounded() {{}}
+    "#
+    )?;
+    Ok(())
+}
@@ -0,0 +1,88 @@
+//@ run-pass
+//! Test that the calling convention of a function ABI can be converted to its internal
+//! representation.
+
+//@ ignore-stage1
+//@ ignore-cross-compile
+//@ ignore-remote
+//@ ignore-windows-gnu mingw has troubles with linking https://github.com/rust-lang/rust/pull/116837
+//@ edition: 2021
+
+#![feature(rustc_private)]
+#![feature(assert_matches)]
+#![feature(control_flow_enum)]
+
+#[macro_use]
+extern crate rustc_smir;
+extern crate rustc_driver;
+extern crate rustc_interface;
+extern crate rustc_middle;
+extern crate rustc_target;
+extern crate stable_mir;
+
+use rustc_middle::ty::TyCtxt;
+use rustc_smir::rustc_internal;
+use rustc_target::abi::call::{Conv, RiscvInterruptKind};
+use stable_mir::abi::CallConvention;
+use stable_mir::mir::mono::Instance;
+use stable_mir::ty::Abi;
+use stable_mir::CrateItems;
+use std::io::Write;
+use std::ops::ControlFlow;
+
+const CRATE_NAME: &str = "input";
+
+fn test_call_conv(tcx: TyCtxt<'_>) -> ControlFlow<()> {
+    let items = stable_mir::all_local_items();
+    let c_abi = Abi::C { unwind: false };
+    let instance = Instance::try_from(*get_item(&items, "callback").unwrap()).unwrap();
+    let conv = instance.fn_abi().unwrap().conv;
+    assert_eq!(conv, CallConvention::C);
+    assert_eq!(rustc_internal::internal_checked(tcx, &conv, &c_abi).unwrap(), Conv::C);
+
+    let stdcall = Abi::Stdcall { unwind: false };
+    let internal = rustc_internal::internal_checked(tcx, CallConvention::X86Stdcall, &stdcall);
+    assert_eq!(internal.unwrap(), Conv::X86Stdcall);
+
+    // The kind of RISC-V interrupts is taken from the ABI.
+    let riscv = CallConvention::RiscvInterrupt;
+    let internal = rustc_internal::internal_checked(tcx, riscv, &Abi::RiscvInterruptS).unwrap();
+    assert_eq!(internal, Conv::RiscvInterrupt { kind: RiscvInterruptKind::Supervisor });
+    let error = rustc_internal::internal_checked(tcx, riscv, &c_abi).unwrap_err();
+    assert!(error.to_string().contains("RISC-V interrupt ABI"), "Unexpected error: {error}");
+    ControlFlow::Continue(())
+}
+
+fn get_item<'a>(items: &'a CrateItems, name: &str) -> Option<&'a stable_mir::CrateItem> {
+    items.iter().find(|crate_item| crate_item.name() == name)
+}
+
+/// This test will generate and analyze a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// Then it will create a `StableMir` using custom arguments and then
+/// it will run the compiler.
+fn main() {
+    let path = "internal_call_conv_input.rs";
+    generate_input(&path).unwrap();
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.to_string(),
+    ];
+    run_with_tcx!(args, test_call_conv).unwrap();
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+    pub extern "C" fn callback(value: u32) -> u32 {{
+        value + 1
+    }}
+    "#
+    )?;
+    Ok(())
+}
@@ -0,0 +1,122 @@
+//@ run-pass
+//! Test that closure and coroutine arguments can be converted to their structured internal
+//! representation, and that their shape is validated.
+
+//@ ignore-stage1
+//@ ignore-cross-compile
+//@ ignore-remote
+//@ ignore-windows-gnu mingw has troubles with linking https://github.com/rust-lang/rust/pull/116837
+//@ edition: 2021
+
+#![feature(rustc_private)]
+#![feature(assert_matches)]
+#![feature(control_flow_enum)]
+
+#[macro_use]
+extern crate rustc_smir;
+extern crate rustc_driver;
+extern crate rustc_interface;
+extern crate rustc_middle;
+extern crate stable_mir;
+
+use rustc_middle::ty::TyCtxt;
+use rustc_smir::rustc_internal;
+use stable_mir::mir::Body;
+use stable_mir::ty::{
+    ClosureDef, ClosureKind, CoroutineDef, GenericArgKind, GenericArgs, RigidTy, TyKind,
+};
+use stable_mir::CrateItems;
+use std::io::Write;
+use std::ops::ControlFlow;
+
+const CRATE_NAME: &str = "input";
+
+fn test_closure_args(tcx: TyCtxt<'_>) -> ControlFlow<()> {
+    let items = stable_mir::all_local_items();
+    let (def, args) = find_closure(&get_item(&items, "apply").unwrap().body());
+
+    let closure_args = rustc_internal::internal_checked(tcx, &args, &def).unwrap();
+    assert_eq!(closure_args.args, rustc_internal::internal(tcx, &args));
+    assert_eq!(closure_args.kind(), rustc_middle::ty::ClosureKind::Fn);
+    let sig = closure_args.sig().skip_binder();
+    assert_eq!(sig.inputs()[0], rustc_middle::ty::Ty::new_tup(tcx, &[tcx.types.u8]));
+    assert_eq!(sig.output(), tcx.types.u8);
+    assert_eq!(closure_args.upvar_tys().len(), 1);
+
+    // Replace the closure kind with `FnMut`.
+    let kind_ty = rustc_internal::internal_closure_kind_ty(tcx, ClosureKind::FnMut);
+    assert_eq!(kind_ty.to_opt_closure_kind(), Some(rustc_middle::ty::ClosureKind::FnMut));
+    let mut fn_mut = args.clone();
+    fn_mut.0[0] = GenericArgKind::Type(rustc_internal::stable(kind_ty));
+    let closure_args = rustc_internal::internal_checked(tcx, &fn_mut, &def).unwrap();
+    assert_eq!(closure_args.kind(), rustc_middle::ty::ClosureKind::FnMut);
+
+    // The closure kind, signature and upvars are all required.
+    let mut missing_upvars = args.clone();
+    missing_upvars.0.pop();
+    let error = rustc_internal::internal_checked(tcx, &missing_upvars, &def).unwrap_err();
+    assert!(
+        error.to_string().contains("Expected 3 generic arguments"),
+        "Unexpected error: {error}"
+    );
+
+    // The closure kind must come before the signature.
+    let mut swapped = args.clone();
+    swapped.0.swap(0, 1);
+    let error = rustc_internal::internal_checked(tcx, &swapped, &def).unwrap_err();
+    assert!(error.to_string().contains("Unexpected closure kind"), "Unexpected error: {error}");
+
+    // Closure arguments do not have the shape of coroutine arguments.
+    let error = rustc_internal::internal_checked(tcx, &args, &CoroutineDef(def.0)).unwrap_err();
+    assert!(
+        error.to_string().contains("Expected 6 generic arguments"),
+        "Unexpected error: {error}"
+    );
+    ControlFlow::Continue(())
+}
+
+/// Find the closure stored in the locals of the given body.
+fn find_closure(body: &Body) -> (ClosureDef, GenericArgs) {
+    body.locals()
+        .iter()
+        .find_map(|local| match local.ty.kind() {
+            TyKind::RigidTy(RigidTy::Closure(def, args)) => Some((def, args)),
+            _ => None,
+        })
+        .unwrap()
+}
+
+fn get_item<'a>(items: &'a CrateItems, name: &str) -> Option<&'a stable_mir::CrateItem> {
+    items.iter().find(|crate_item| crate_item.name() == name)
+}
+
+/// This test will generate and analyze a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// Then it will create a `StableMir` using custom arguments and then
+/// it will run the compiler.
+fn main() {
+    let path = "internal_closure_input.rs";
+    generate_input(&path).unwrap();
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.to_string(),
+    ];
+    run_with_tcx!(args, test_closure_args).unwrap();
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+    pub fn apply(offset: u8) -> u8 {{
+        let add = |value: u8| value + offset;
+        add(1)
+    }}
+    "#
+    )?;
+    Ok(())
+}
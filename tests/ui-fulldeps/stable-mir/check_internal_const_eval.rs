@@ -0,0 +1,135 @@
+//@ run-pass
+//! Test that an unevaluated constant can be converted together with the environment it should be
+//! evaluated in, including with generic arguments other than the ones it was used with.
+
+//@ ignore-stage1
+//@ ignore-cross-compile
+//@ ignore-remote
+//@ ignore-windows-gnu mingw has troubles with linking https://github.com/rust-lang/rust/pull/116837
+//@ edition: 2021
+
+#![feature(rustc_private)]
+#![feature(assert_matches)]
+#![feature(control_flow_enum)]
+
+#[macro_use]
+extern crate rustc_smir;
+extern crate rustc_driver;
+extern crate rustc_interface;
+extern crate rustc_middle;
+extern crate rustc_span;
+extern crate stable_mir;
+
+use rustc_middle::ty::{TyCtxt, TypeVisitableExt};
+use rustc_smir::rustc_internal;
+use rustc_span::DUMMY_SP;
+use stable_mir::crate_def::CrateDef;
+use stable_mir::mir::{Operand, Rvalue, StatementKind};
+use stable_mir::ty::{
+    ClauseKind, ConstantKind, GenericArgKind, GenericArgs, ParamEnv, PredicatePolarity, Reveal,
+    TraitPredicate, TraitRef, Ty, UintTy, UnevaluatedConst,
+};
+use stable_mir::CrateItems;
+use std::io::Write;
+use std::ops::ControlFlow;
+
+const CRATE_NAME: &str = "input";
+
+fn test_const_eval(tcx: TyCtxt<'_>) -> ControlFlow<()> {
+    let items = stable_mir::all_local_items();
+    let body = get_item(&items, "size").unwrap().body();
+    let constant = body
+        .blocks
+        .iter()
+        .flat_map(|block| &block.statements)
+        .find_map(|statement| match &statement.kind {
+            StatementKind::Assign(_, Rvalue::Use(Operand::Constant(constant))) => {
+                Some(constant.const_.clone())
+            }
+            _ => None,
+        })
+        .unwrap();
+    let ConstantKind::Unevaluated(generic) = constant.kind() else {
+        unreachable!("Expected an unevaluated constant, but found: {constant:?}")
+    };
+
+    // `<u32 as Bytes>::SIZE` in an empty environment.
+    let u32_ty = Ty::unsigned_ty(UintTy::U32);
+    let concrete = UnevaluatedConst {
+        args: GenericArgs(vec![GenericArgKind::Type(u32_ty)]),
+        ..generic.clone()
+    };
+    let empty = ParamEnv { caller_bounds: vec![], reveal: Reveal::All };
+    let (param_env, uneval) =
+        rustc_internal::internal_unevaluated_const(tcx, &concrete, &empty).unwrap();
+    assert!(!uneval.args.has_param());
+    let value = tcx.const_eval_resolve(param_env, uneval, DUMMY_SP).unwrap();
+    assert_eq!(value.try_to_target_usize(tcx), Some(4));
+
+    // `<T as Bytes>::SIZE` cannot be evaluated, even if `T: Bytes` holds.
+    let bytes = stable_mir::all_trait_decls()
+        .into_iter()
+        .find(|def| def.trimmed_name() == "Bytes")
+        .unwrap();
+    let param_ty = *generic.args.0[0].expect_ty();
+    let clause = ClauseKind::Trait(TraitPredicate {
+        trait_ref: TraitRef::new(bytes, param_ty, &GenericArgs(vec![])),
+        polarity: PredicatePolarity::Positive,
+    });
+    let env = ParamEnv { caller_bounds: vec![clause], reveal: Reveal::UserFacing };
+    let (param_env, uneval) =
+        rustc_internal::internal_unevaluated_const(tcx, generic, &env).unwrap();
+    assert_eq!(param_env.caller_bounds().len(), 1);
+    assert!(tcx.const_eval_resolve(param_env, uneval, DUMMY_SP).is_err());
+
+    // The arguments must match the generics of the constant.
+    let missing = UnevaluatedConst { args: GenericArgs(vec![]), ..generic.clone() };
+    let error = rustc_internal::internal_unevaluated_const(tcx, &missing, &empty).unwrap_err();
+    assert!(
+        error.to_string().contains("Expected 1 generic arguments"),
+        "Unexpected error: {error}"
+    );
+    ControlFlow::Continue(())
+}
+
+fn get_item<'a>(items: &'a CrateItems, name: &str) -> Option<&'a stable_mir::CrateItem> {
+    items.iter().find(|crate_item| crate_item.name() == name)
+}
+
+/// This test will generate and analyze a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// Then it will create a `StableMir` using custom arguments and then
+/// it will run the compiler.
+fn main() {
+    let path = "internal_const_eval_input.rs";
+    generate_input(&path).unwrap();
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.to_string(),
+    ];
+    run_with_tcx!(args, test_const_eval).unwrap();
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+    pub trait Bytes {{
+        const SIZE: usize;
+    }}
+
+    impl Bytes for u32 {{
+        const SIZE: usize = 4;
+    }}
+
+    pub fn size<T: Bytes>() -> usize {{
+        T::SIZE
+    }}
+    "#
+    )?;
+    Ok(())
+}
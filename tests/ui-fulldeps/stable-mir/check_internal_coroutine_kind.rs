@@ -0,0 +1,87 @@
+//@ run-pass
+//! Test that the kinds of coroutines can be converted to their internal representation,
+//! including the coroutine of an async closure.
+
+//@ ignore-stage1
+//@ ignore-cross-compile
+//@ ignore-remote
+//@ ignore-windows-gnu mingw has troubles with linking https://github.com/rust-lang/rust/pull/116837
+//@ edition: 2021
+
+#![feature(rustc_private)]
+#![feature(assert_matches)]
+#![feature(control_flow_enum)]
+
+#[macro_use]
+extern crate rustc_smir;
+extern crate rustc_driver;
+extern crate rustc_interface;
+extern crate rustc_middle;
+extern crate stable_mir;
+
+use rustc_middle::ty::TyCtxt;
+use rustc_smir::rustc_internal;
+use stable_mir::mir::{CoroutineDesugaring, CoroutineKind, CoroutineSource};
+use std::io::Write;
+use std::ops::ControlFlow;
+
+const CRATE_NAME: &str = "input";
+
+fn test_coroutine_kind(tcx: TyCtxt<'_>) -> ControlFlow<()> {
+    let mut sources = vec![];
+    for def_id in tcx.hir().body_owners() {
+        let Some(kind) = tcx.coroutine_kind(def_id) else { continue };
+        let stable = rustc_internal::stable(kind);
+        assert_eq!(rustc_internal::internal(tcx, &stable), kind);
+        let CoroutineKind::Desugared(CoroutineDesugaring::Async, source) = stable else {
+            unreachable!("Expected an async coroutine, but found: {stable:?}")
+        };
+        sources.push(source);
+    }
+    // The body of the async closure is a closure coroutine.
+    assert!(sources.contains(&CoroutineSource::Closure), "Unexpected sources: {sources:?}");
+    assert!(sources.contains(&CoroutineSource::Block), "Unexpected sources: {sources:?}");
+    assert!(sources.contains(&CoroutineSource::Fn), "Unexpected sources: {sources:?}");
+    ControlFlow::Continue(())
+}
+
+/// This test will generate and analyze a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// Then it will create a `StableMir` using custom arguments and then
+/// it will run the compiler.
+fn main() {
+    let path = "internal_coroutine_kind_input.rs";
+    generate_input(&path).unwrap();
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        "--edition=2021".to_string(),
+        path.to_string(),
+    ];
+    run_with_tcx!(args, test_coroutine_kind).unwrap();
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+    #![feature(async_closure)]
+
+    pub async fn value() -> u32 {{
+        1
+    }}
+
+    pub fn closure() -> impl Sized {{
+        async || value().await
+    }}
+
+    pub fn block() -> impl Sized {{
+        async {{ value().await }}
+    }}
+    "#
+    )?;
+    Ok(())
+}
@@ -0,0 +1,97 @@
+//@ run-pass
+//! Test that the principal trait of a `dyn*` type is validated when converting the type to its
+//! internal representation.
+
+//@ ignore-stage1
+//@ ignore-cross-compile
+//@ ignore-remote
+//@ ignore-windows-gnu mingw has troubles with linking https://github.com/rust-lang/rust/pull/116837
+//@ edition: 2021
+
+#![feature(rustc_private)]
+#![feature(assert_matches)]
+#![feature(control_flow_enum)]
+
+#[macro_use]
+extern crate rustc_smir;
+extern crate rustc_driver;
+extern crate rustc_interface;
+extern crate rustc_middle;
+extern crate stable_mir;
+
+use rustc_middle::ty::TyCtxt;
+use rustc_smir::rustc_internal;
+use stable_mir::crate_def::CrateDef;
+use stable_mir::ty::{
+    Binder, DynKind, ExistentialPredicate, ExistentialTraitRef, GenericArgs, RigidTy, TyKind,
+};
+use stable_mir::CrateItems;
+use std::io::Write;
+use std::ops::ControlFlow;
+
+const CRATE_NAME: &str = "input";
+
+fn test_dyn_star(tcx: TyCtxt<'_>) -> ControlFlow<()> {
+    let items = stable_mir::all_local_items();
+    let body = get_item(&items, "show").unwrap().body();
+    let TyKind::RigidTy(rigid) = body.locals()[1].ty.kind() else { unreachable!() };
+    let RigidTy::Dynamic(predicates, region, DynKind::DynStar) = &rigid else {
+        unreachable!("Expected a `dyn*` type, but found: {rigid:?}")
+    };
+
+    let internal = rustc_internal::internal_checked(tcx, &rigid, &()).unwrap();
+    assert_eq!(internal, rustc_internal::internal(tcx, &rigid));
+
+    // `dyn* Clone` is not a valid type since `Clone` is not object safe.
+    let clone = stable_mir::all_trait_decls()
+        .into_iter()
+        .find(|def| def.trimmed_name() == "Clone")
+        .unwrap();
+    let principal = ExistentialTraitRef { def_id: clone, generic_args: GenericArgs(vec![]) };
+    let bound_vars = predicates[0].bound_vars.clone();
+    let clone_predicates =
+        vec![Binder { value: ExistentialPredicate::Trait(principal), bound_vars }];
+    let invalid = RigidTy::Dynamic(clone_predicates, region.clone(), DynKind::DynStar);
+    let error = rustc_internal::internal_checked(tcx, &invalid, &()).unwrap_err();
+    assert!(error.to_string().contains("Expected an object safe trait"), "Unexpected error: {error}");
+    ControlFlow::Continue(())
+}
+
+fn get_item<'a>(items: &'a CrateItems, name: &str) -> Option<&'a stable_mir::CrateItem> {
+    items.iter().find(|crate_item| crate_item.name() == name)
+}
+
+/// This test will generate and analyze a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// Then it will create a `StableMir` using custom arguments and then
+/// it will run the compiler.
+fn main() {
+    let path = "internal_dyn_star_input.rs";
+    generate_input(&path).unwrap();
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.to_string(),
+    ];
+    run_with_tcx!(args, test_dyn_star).unwrap();
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+    #![feature(dyn_star)]
+    #![allow(incomplete_features)]
+
+    use std::fmt::Debug;
+
+    pub fn show(value: dyn* Debug) -> String {{
+        format!("{{value:?}}")
+    }}
+    "#
+    )?;
+    Ok(())
+}
@@ -0,0 +1,89 @@
+//@ run-pass
+//! Test that the shape of the fields in a layout can be converted to its internal
+//! representation, including how the fields were reordered.
+
+//@ ignore-stage1
+//@ ignore-cross-compile
+//@ ignore-remote
+//@ ignore-windows-gnu mingw has troubles with linking https://github.com/rust-lang/rust/pull/116837
+//@ edition: 2021
+
+#![feature(rustc_private)]
+#![feature(assert_matches)]
+#![feature(control_flow_enum)]
+
+#[macro_use]
+extern crate rustc_smir;
+extern crate rustc_driver;
+extern crate rustc_interface;
+extern crate rustc_middle;
+extern crate stable_mir;
+
+use rustc_middle::ty::TyCtxt;
+use rustc_smir::rustc_internal;
+use stable_mir::abi::FieldsShape;
+use stable_mir::CrateItems;
+use std::io::Write;
+use std::ops::ControlFlow;
+
+const CRATE_NAME: &str = "input";
+
+fn test_fields_shape(tcx: TyCtxt<'_>) -> ControlFlow<()> {
+    let items = stable_mir::all_local_items();
+    let body = get_item(&items, "reorder").unwrap().body();
+    let ty = body.locals()[1].ty;
+    let shape = ty.layout().unwrap().shape().fields;
+    let FieldsShape::Arbitrary { offsets, memory_index } = &shape else {
+        unreachable!("Expected arbitrary fields, but found: {shape:?}")
+    };
+    assert_eq!(offsets.len(), 3);
+    // The fields are reordered to avoid padding.
+    assert_ne!(memory_index, &[0, 1, 2]);
+
+    let internal = rustc_internal::internal(tcx, &shape);
+    let param_env = rustc_middle::ty::ParamEnv::reveal_all();
+    let layout = tcx.layout_of(param_env.and(rustc_internal::internal(tcx, ty))).unwrap();
+    assert_eq!(internal, layout.fields);
+    assert_eq!(rustc_internal::stable(&internal), shape);
+    ControlFlow::Continue(())
+}
+
+fn get_item<'a>(items: &'a CrateItems, name: &str) -> Option<&'a stable_mir::CrateItem> {
+    items.iter().find(|crate_item| crate_item.name() == name)
+}
+
+/// This test will generate and analyze a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// Then it will create a `StableMir` using custom arguments and then
+/// it will run the compiler.
+fn main() {
+    let path = "internal_fields_shape_input.rs";
+    generate_input(&path).unwrap();
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.to_string(),
+    ];
+    run_with_tcx!(args, test_fields_shape).unwrap();
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+    pub struct Reordered {{
+        pub small: u8,
+        pub large: u32,
+        pub medium: u16,
+    }}
+
+    pub fn reorder(value: Reordered) -> u32 {{
+        value.large
+    }}
+    "#
+    )?;
+    Ok(())
+}
@@ -0,0 +1,173 @@
+//@ run-pass
+//! Test that function signatures, their binders and their ABIs are validated when converting them
+//! to their internal representation.
+
+//@ ignore-stage1
+//@ ignore-cross-compile
+//@ ignore-remote
+//@ ignore-windows-gnu mingw has troubles with linking https://github.com/rust-lang/rust/pull/116837
+//@ edition: 2021
+
+#![feature(rustc_private)]
+#![feature(assert_matches)]
+#![feature(control_flow_enum)]
+
+#[macro_use]
+extern crate rustc_smir;
+extern crate rustc_driver;
+extern crate rustc_interface;
+extern crate rustc_middle;
+extern crate stable_mir;
+
+use rustc_middle::ty::TyCtxt;
+use rustc_smir::rustc_internal;
+use stable_mir::crate_def::CrateDef;
+use stable_mir::mir::{Body, Safety, TerminatorKind};
+use stable_mir::ty::{Abi, Binder, FnSig, GenericArgs, RigidTy, TyKind};
+use stable_mir::CrateItems;
+use std::io::Write;
+use std::ops::ControlFlow;
+
+const CRATE_NAME: &str = "input";
+
+fn test_fn_sig(tcx: TyCtxt<'_>) -> ControlFlow<()> {
+    let items = stable_mir::all_local_items();
+    let call = get_item(&items, "call").unwrap();
+    let body = call.body();
+    let sig = body.locals()[1].ty.kind().fn_sig().unwrap().skip_binder();
+    assert!(sig.c_variadic);
+
+    let internal = rustc_internal::internal_checked(tcx, &sig, &()).unwrap();
+    assert!(internal.c_variadic);
+    assert_eq!(internal.inputs().len(), 1);
+    assert_eq!(internal, rustc_internal::internal(tcx, &sig));
+
+    // `unsafe extern "C" fn(i32, ...)`
+    let unsafe_sig = FnSig { safety: Safety::Unsafe, ..sig.clone() };
+    let internal = rustc_internal::internal_checked(tcx, &unsafe_sig, &()).unwrap();
+    assert_eq!(internal.safety, rustc_internal::internal(tcx, Safety::Unsafe));
+
+    // Variadic arguments require a C compatible ABI.
+    let rust_abi = FnSig { abi: Abi::Rust, ..sig.clone() };
+    let error = rustc_internal::internal_checked(tcx, &rust_abi, &()).unwrap_err();
+    assert!(error.to_string().contains("does not support C-variadic"), "Unexpected error: {error}");
+
+    // Drop the only fixed argument.
+    let no_fixed_args = FnSig { inputs_and_output: vec![sig.output()], ..sig };
+    let error = rustc_internal::internal_checked(tcx, &no_fixed_args, &()).unwrap_err();
+    assert!(error.to_string().contains("at least one fixed argument"), "Unexpected error: {error}");
+
+    check_binder(tcx, &get_item(&items, "bound").unwrap().body());
+    check_unwind(tcx, &get_item(&items, "unwind").unwrap().body());
+    check_fn_def(tcx, &get_item(&items, "append").unwrap().body());
+    ControlFlow::Continue(())
+}
+
+/// Check that the variables bound in a signature must be declared by its binder.
+fn check_binder(tcx: TyCtxt<'_>, body: &Body) {
+    let fn_ptr = body.locals()[1].ty;
+    let sig = fn_ptr.kind().fn_sig().unwrap();
+    assert_eq!(sig.bound_vars.len(), 1);
+
+    let internal = rustc_internal::internal_checked(tcx, &sig, &()).unwrap();
+    assert_eq!(internal, rustc_internal::internal(tcx, fn_ptr).fn_sig(tcx));
+
+    // Rebuilding the pointer type from its signature keeps `'a` bound by the pointer.
+    let kind = rustc_internal::internal(tcx, RigidTy::FnPtr(sig.clone()));
+    assert_eq!(&kind, rustc_internal::internal(tcx, fn_ptr).kind());
+    let rustc_middle::ty::FnPtr(sig_tys, _) = kind else { unreachable!() };
+    assert_eq!(sig_tys.bound_vars().len(), 1);
+    let rustc_middle::ty::Ref(region, _, _) = sig_tys.skip_binder().inputs()[0].kind() else {
+        unreachable!()
+    };
+    assert!(region.is_bound());
+
+    // Drop the declaration of `'a`.
+    let missing_var = Binder { value: sig.value.clone(), bound_vars: vec![] };
+    let error = rustc_internal::internal_checked(tcx, &missing_var, &()).unwrap_err();
+    assert!(error.to_string().contains("is out of range"), "Unexpected error: {error}");
+}
+
+/// Check that the unwind flag of an ABI is kept by the conversion.
+fn check_unwind(tcx: TyCtxt<'_>, body: &Body) {
+    let c_sig = body.locals()[1].ty.kind().fn_sig().unwrap().skip_binder();
+    let unwind_sig = body.locals()[2].ty.kind().fn_sig().unwrap().skip_binder();
+    assert_eq!(c_sig.abi, Abi::C { unwind: false });
+    assert_eq!(unwind_sig.abi, Abi::C { unwind: true });
+
+    for sig in [&c_sig, &unwind_sig] {
+        let internal = rustc_internal::internal(tcx, &sig.abi);
+        assert_eq!(internal, rustc_internal::internal(tcx, sig).abi);
+        assert_eq!(rustc_internal::stable(internal), sig.abi);
+    }
+    assert_eq!(rustc_internal::internal(tcx, &c_sig.abi).name(), "C");
+    assert_eq!(rustc_internal::internal(tcx, &unwind_sig.abi).name(), "C-unwind");
+}
+
+/// Check the signature of `Vec::<u8>::push` instantiated from its definition and arguments.
+fn check_fn_def(tcx: TyCtxt<'_>, body: &Body) {
+    let func = body
+        .blocks
+        .iter()
+        .find_map(|block| match &block.terminator.kind {
+            TerminatorKind::Call { func, .. } => Some(func),
+            _ => None,
+        })
+        .unwrap();
+    let fn_ty = func.ty(body.locals()).unwrap();
+    let TyKind::RigidTy(RigidTy::FnDef(def, args)) = fn_ty.kind() else { unreachable!() };
+    assert!(def.name().ends_with("::push"), "Unexpected function: {}", def.name());
+
+    let sig = rustc_internal::internal_fn_sig(tcx, def, &args).unwrap();
+    assert_eq!(sig, rustc_internal::internal(tcx, fn_ty).fn_sig(tcx));
+    let inputs = sig.skip_binder().inputs();
+    assert_eq!(inputs.len(), 2);
+    assert_eq!(inputs[1], tcx.types.u8);
+    assert!(sig.skip_binder().output().is_unit());
+
+    let error = rustc_internal::internal_fn_sig(tcx, def, &GenericArgs(vec![])).unwrap_err();
+    assert!(
+        error.to_string().contains("Expected 2 generic arguments"),
+        "Unexpected error: {error}"
+    );
+}
+
+fn get_item<'a>(items: &'a CrateItems, name: &str) -> Option<&'a stable_mir::CrateItem> {
+    items.iter().find(|crate_item| crate_item.name() == name)
+}
+
+/// This test will generate and analyze a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// Then it will create a `StableMir` using custom arguments and then
+/// it will run the compiler.
+fn main() {
+    let path = "internal_fn_sig_input.rs";
+    generate_input(&path).unwrap();
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.to_string(),
+    ];
+    run_with_tcx!(args, test_fn_sig).unwrap();
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+    pub fn call(_f: extern "C" fn(i32, ...)) {{}}
+
+    pub fn bound(_f: for<'a> fn(&'a u8)) {{}}
+
+    pub fn unwind(_c: extern "C" fn(), _c_unwind: extern "C-unwind" fn()) {{}}
+
+    pub fn append(values: &mut Vec<u8>) {{
+        values.push(1);
+    }}
+    "#
+    )?;
+    Ok(())
+}
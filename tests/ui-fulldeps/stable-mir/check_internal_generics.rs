@@ -0,0 +1,118 @@
+//@ run-pass
+//! Test that generics and their parameters can be converted to their internal representation, and
+//! that generics are validated against the definition they belong to.
+
+//@ ignore-stage1
+//@ ignore-cross-compile
+//@ ignore-remote
+//@ ignore-windows-gnu mingw has troubles with linking https://github.com/rust-lang/rust/pull/116837
+//@ edition: 2021
+
+#![feature(rustc_private)]
+#![feature(assert_matches)]
+#![feature(control_flow_enum)]
+
+#[macro_use]
+extern crate rustc_smir;
+extern crate rustc_driver;
+extern crate rustc_interface;
+extern crate rustc_middle;
+extern crate stable_mir;
+
+use rustc_middle::ty::TyCtxt;
+use rustc_smir::rustc_internal;
+use stable_mir::crate_def::CrateDef;
+use stable_mir::ty::{AdtDef, GenericParamDefKind, Generics, RigidTy, TyKind};
+use stable_mir::CrateItems;
+use std::io::Write;
+use std::ops::ControlFlow;
+
+const CRATE_NAME: &str = "input";
+
+fn test_generics(tcx: TyCtxt<'_>) -> ControlFlow<()> {
+    let items = stable_mir::all_local_items();
+    let body = get_item(&items, "len").unwrap().body();
+    let hash_map = adt_def(&body.locals()[1].ty.kind());
+    let vec = adt_def(&body.locals()[2].ty.kind());
+
+    let generics = generics_of(tcx, hash_map);
+    let kinds: Vec<_> = generics.params.iter().map(|param| param.kind.clone()).collect();
+    let no_default = GenericParamDefKind::Type { has_default: false, synthetic: false };
+    let default = GenericParamDefKind::Type { has_default: true, synthetic: false };
+    assert_eq!(kinds, [no_default.clone(), no_default, default]);
+
+    let internal = rustc_internal::internal_checked(tcx, &generics, &hash_map.def_id()).unwrap();
+    assert_eq!(internal.own_params.len(), 3);
+    for (param, expected) in generics.params.iter().zip(&internal.own_params) {
+        let param = rustc_internal::internal(tcx, param);
+        assert_eq!(param.def_id, expected.def_id);
+        assert_eq!(param.index, expected.index);
+        assert_eq!(param.name, expected.name);
+    }
+
+    // The default of `S` is `RandomState`.
+    let hasher = rustc_internal::internal(tcx, &generics.params[2]);
+    let default_ty = tcx.type_of(hasher.def_id).instantiate_identity();
+    let rustc_middle::ty::Adt(default_def, _) = default_ty.kind() else {
+        unreachable!("Expected `RandomState`, but found: {default_ty}")
+    };
+    assert_eq!(tcx.item_name(default_def.did()).as_str(), "RandomState");
+
+    // The generics of `Vec` do not belong to `HashMap`.
+    let error = rustc_internal::internal_checked(tcx, &generics_of(tcx, vec), &hash_map.def_id())
+        .unwrap_err();
+    assert!(
+        error.to_string().contains("Expected 0 parent and 3 own generic parameters"),
+        "Unexpected error: {error}"
+    );
+    ControlFlow::Continue(())
+}
+
+/// There is no stable API to retrieve the generics of an ADT, so get them from the compiler.
+fn generics_of(tcx: TyCtxt<'_>, def: AdtDef) -> Generics {
+    let def_id = rustc_internal::internal(tcx, def.def_id());
+    rustc_internal::stable(tcx.generics_of(def_id))
+}
+
+fn adt_def(kind: &TyKind) -> AdtDef {
+    let TyKind::RigidTy(RigidTy::Adt(def, _)) = kind else {
+        unreachable!("Expected an ADT, but found: {kind:?}")
+    };
+    *def
+}
+
+fn get_item<'a>(items: &'a CrateItems, name: &str) -> Option<&'a stable_mir::CrateItem> {
+    items.iter().find(|crate_item| crate_item.name() == name)
+}
+
+/// This test will generate and analyze a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// Then it will create a `StableMir` using custom arguments and then
+/// it will run the compiler.
+fn main() {
+    let path = "internal_generics_input.rs";
+    generate_input(&path).unwrap();
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.to_string(),
+    ];
+    run_with_tcx!(args, test_generics).unwrap();
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+    use std::collections::HashMap;
+
+    pub fn len(map: HashMap<u8, u16>, values: Vec<u8>) -> usize {{
+        map.len() + values.len()
+    }}
+    "#
+    )?;
+    Ok(())
+}
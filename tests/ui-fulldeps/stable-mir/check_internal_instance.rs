@@ -0,0 +1,174 @@
+//@ run-pass
+//! Test that instance kinds can be converted to their internal representation, including the
+//! compiler generated shims, and that mono items can be built from their parts.
+
+//@ ignore-stage1
+//@ ignore-cross-compile
+//@ ignore-remote
+//@ ignore-windows-gnu mingw has troubles with linking https://github.com/rust-lang/rust/pull/116837
+//@ edition: 2021
+
+#![feature(rustc_private)]
+#![feature(assert_matches)]
+#![feature(control_flow_enum)]
+
+#[macro_use]
+extern crate rustc_smir;
+extern crate rustc_driver;
+extern crate rustc_interface;
+extern crate rustc_middle;
+extern crate stable_mir;
+
+use rustc_middle::ty::TyCtxt;
+use rustc_smir::rustc_internal;
+use stable_mir::crate_def::CrateDef;
+use stable_mir::mir::mono::{Instance, InstanceKind};
+use stable_mir::mir::{Body, TerminatorKind};
+use stable_mir::ty::{FnDef, GenericArgs, RigidTy, TyKind};
+use stable_mir::CrateItems;
+use std::assert_matches::assert_matches;
+use std::io::Write;
+use std::ops::ControlFlow;
+
+const CRATE_NAME: &str = "input";
+
+fn test_instance_kind(tcx: TyCtxt<'_>) -> ControlFlow<()> {
+    let items = stable_mir::all_local_items();
+    let body = get_item(&items, "duplicate").unwrap().body();
+
+    let drop_glue = Instance::resolve_drop_in_place(body.locals()[2].ty);
+    let kind = check_instance(tcx, drop_glue);
+    assert_matches!(kind, rustc_middle::ty::InstanceKind::DropGlue(_, Some(_)));
+
+    let clone_shim = resolve_call(&body);
+    let kind = check_instance(tcx, clone_shim);
+    assert_matches!(kind, rustc_middle::ty::InstanceKind::CloneShim(..));
+
+    // A user defined item is not a shim.
+    let item = Instance::try_from(*get_item(&items, "duplicate").unwrap()).unwrap();
+    let context = (item.def.def_id(), item.args());
+    let error = rustc_internal::internal_checked(tcx, InstanceKind::Shim, &context).unwrap_err();
+    assert!(error.to_string().contains("does not resolve to a shim"), "Unexpected error: {error}");
+
+    check_mono_fn(tcx, &get_item(&items, "make").unwrap().body());
+    check_shim_args(tcx, &get_item(&items, "names").unwrap().body());
+    ControlFlow::Continue(())
+}
+
+/// Check that the arguments of the drop glue for `Vec<String>` can be built from their parts.
+fn check_shim_args(tcx: TyCtxt<'_>, body: &Body) {
+    let drop_glue = Instance::resolve_drop_in_place(body.locals()[1].ty);
+    let (def, args) = (drop_glue.def.def_id(), drop_glue.args());
+    let internal = rustc_internal::internal_shim_args(tcx, def, &args).unwrap();
+    assert_eq!(internal, rustc_internal::internal(tcx, drop_glue).args);
+
+    // `Vec::<u8>::new` is not a shim.
+    let item = resolve_call(&get_item(&stable_mir::all_local_items(), "make").unwrap().body());
+    let error = rustc_internal::internal_shim_args(tcx, item.def.def_id(), &item.args());
+    let error = error.unwrap_err();
+    assert!(error.to_string().contains("does not resolve to a shim"), "Unexpected error: {error}");
+}
+
+/// Check that a mono item for `Vec::<u8>::new` can be built from its parts.
+fn check_mono_fn(tcx: TyCtxt<'_>, body: &Body) {
+    let expected = resolve_call(body);
+    let (def, args) = (expected.def.def_id(), expected.args());
+    let item = rustc_internal::internal_mono_fn(tcx, InstanceKind::Item, def, &args).unwrap();
+    let rustc_middle::mir::mono::MonoItem::Fn(instance) = item else {
+        unreachable!("Expected a function, but found: {item:?}")
+    };
+    assert_eq!(instance, rustc_internal::internal(tcx, expected));
+    assert_eq!(tcx.item_name(instance.def_id()).as_str(), "new");
+
+    // `Vec::<T>::new` is not monomorphic.
+    let identity = rustc_middle::ty::GenericArgs::identity_for_item(tcx, instance.def_id());
+    let generic_args = rustc_internal::stable(identity);
+    let error =
+        rustc_internal::internal_mono_fn(tcx, InstanceKind::Item, def, &generic_args).unwrap_err();
+    assert!(error.to_string().contains("Expected monomorphic"), "Unexpected error: {error}");
+
+    // `<Vec<u8> as Default>::default` resolves to the method of the implementation.
+    let body = get_item(&stable_mir::all_local_items(), "make_default").unwrap().body();
+    let (def, args) = called_fn(&body);
+    let error = rustc_internal::internal_mono_fn(tcx, InstanceKind::Item, def.def_id(), &args);
+    let error = error.unwrap_err();
+    assert!(error.to_string().contains("to resolve to itself"), "Unexpected error: {error}");
+}
+
+/// Check that the converted kind matches the kind of the internal instance.
+fn check_instance<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    instance: Instance,
+) -> rustc_middle::ty::InstanceKind<'tcx> {
+    assert_eq!(instance.kind, InstanceKind::Shim);
+    let context = (instance.def.def_id(), instance.args());
+    let kind = rustc_internal::internal_checked(tcx, instance.kind, &context).unwrap();
+    assert_eq!(kind, rustc_internal::internal(tcx, instance).def);
+    kind
+}
+
+/// Resolve the instance called by the first call terminator of the body.
+fn resolve_call(body: &Body) -> Instance {
+    let (def, args) = called_fn(body);
+    Instance::resolve(def, &args).unwrap()
+}
+
+/// Find the function called by the first call terminator of the body.
+fn called_fn(body: &Body) -> (FnDef, GenericArgs) {
+    let func = body
+        .blocks
+        .iter()
+        .find_map(|block| match &block.terminator.kind {
+            TerminatorKind::Call { func, .. } => Some(func),
+            _ => None,
+        })
+        .unwrap();
+    let TyKind::RigidTy(RigidTy::FnDef(def, args)) = func.ty(body.locals()).unwrap().kind() else {
+        unreachable!("Expected a function definition")
+    };
+    (def, args)
+}
+
+fn get_item<'a>(items: &'a CrateItems, name: &str) -> Option<&'a stable_mir::CrateItem> {
+    items.iter().find(|crate_item| crate_item.name() == name)
+}
+
+/// This test will generate and analyze a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// Then it will create a `StableMir` using custom arguments and then
+/// it will run the compiler.
+fn main() {
+    let path = "internal_instance_input.rs";
+    generate_input(&path).unwrap();
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.to_string(),
+    ];
+    run_with_tcx!(args, test_instance_kind).unwrap();
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+    pub fn duplicate(values: (u8, u16), _owned: Vec<u8>) -> (u8, u16) {{
+        values.clone()
+    }}
+
+    pub fn make() -> Vec<u8> {{
+        Vec::new()
+    }}
+
+    pub fn make_default() -> Vec<u8> {{
+        Default::default()
+    }}
+
+    pub fn names(_names: Vec<String>) {{}}
+    "#
+    )?;
+    Ok(())
+}
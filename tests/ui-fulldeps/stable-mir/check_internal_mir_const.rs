@@ -0,0 +1,87 @@
+//@ run-pass
+//! Test that type system constants used in MIR are validated against their declared type when
+//! converting them to their internal representation.
+
+//@ ignore-stage1
+//@ ignore-cross-compile
+//@ ignore-remote
+//@ ignore-windows-gnu mingw has troubles with linking https://github.com/rust-lang/rust/pull/116837
+//@ edition: 2021
+
+#![feature(rustc_private)]
+#![feature(assert_matches)]
+#![feature(control_flow_enum)]
+
+#[macro_use]
+extern crate rustc_smir;
+extern crate rustc_driver;
+extern crate rustc_interface;
+extern crate rustc_middle;
+extern crate stable_mir;
+
+use rustc_middle::ty::TyCtxt;
+use rustc_smir::rustc_internal;
+use stable_mir::ty::{ConstantKind, MirConst, Ty, TyConst, UintTy};
+use std::io::Write;
+use std::ops::ControlFlow;
+
+const CRATE_NAME: &str = "input";
+
+fn test_mir_const(tcx: TyCtxt<'_>) -> ControlFlow<()> {
+    // `5usize`
+    let ty_const = TyConst::try_from_target_usize(5).unwrap();
+    let usize_ty = Ty::usize_ty();
+    let id = MirConst::try_from_uint(5, UintTy::Usize).unwrap().id;
+    let constant = MirConst::new(ConstantKind::Ty(ty_const.clone()), usize_ty, id);
+    let internal = rustc_internal::internal_checked(tcx, &constant, &()).unwrap();
+    let rustc_middle::mir::Const::Ty(ty, ct) = internal else {
+        unreachable!("Expected a type system constant, but found: {internal:?}")
+    };
+    assert_eq!(ty, tcx.types.usize);
+    assert_eq!(ct, rustc_internal::internal(tcx, &ty_const));
+
+    // A `usize` value cannot be used as a `u32` constant.
+    let u32_ty = Ty::unsigned_ty(UintTy::U32);
+    let mismatched = MirConst::new(ConstantKind::Ty(ty_const), u32_ty, id);
+    let error = rustc_internal::internal_checked(tcx, &mismatched, &()).unwrap_err();
+    assert!(
+        error.to_string().contains("Expected a constant of type `u32`"),
+        "Unexpected error: {error}"
+    );
+
+    // The converted constant is paired with its type.
+    let value = MirConst::try_from_uint(7, UintTy::U16).unwrap();
+    let (internal, ty) = rustc_internal::internal_mir_const_with_ty(tcx, &value);
+    assert_eq!(ty, tcx.types.u16);
+    assert_eq!(ty, internal.ty());
+    assert_eq!(internal, rustc_internal::internal(tcx, &value));
+    ControlFlow::Continue(())
+}
+
+/// This test will generate and analyze a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// Then it will create a `StableMir` using custom arguments and then
+/// it will run the compiler.
+fn main() {
+    let path = "internal_mir_const_input.rs";
+    generate_input(&path).unwrap();
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.to_string(),
+    ];
+    run_with_tcx!(args, test_mir_const).unwrap();
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+    pub const FIVE: usize = 5;
+    "#
+    )?;
+    Ok(())
+}
@@ -0,0 +1,93 @@
+//@ run-pass
+//! Test that stable types can be normalized while converting them to their internal
+//! representation.
+
+//@ ignore-stage1
+//@ ignore-cross-compile
+//@ ignore-remote
+//@ ignore-windows-gnu mingw has troubles with linking https://github.com/rust-lang/rust/pull/116837
+//@ edition: 2021
+
+#![feature(rustc_private)]
+#![feature(assert_matches)]
+#![feature(control_flow_enum)]
+
+#[macro_use]
+extern crate rustc_smir;
+extern crate rustc_driver;
+extern crate rustc_interface;
+extern crate rustc_middle;
+extern crate stable_mir;
+
+use rustc_middle::ty::TyCtxt;
+use rustc_smir::rustc_internal;
+use stable_mir::ty::{ParamEnv, Reveal};
+use stable_mir::CrateItems;
+use std::io::Write;
+use std::ops::ControlFlow;
+
+const CRATE_NAME: &str = "input";
+
+fn test_normalize(tcx: TyCtxt<'_>) -> ControlFlow<()> {
+    let items = stable_mir::all_local_items();
+    let param_env = ParamEnv { caller_bounds: vec![], reveal: Reveal::All };
+
+    // `<Vec<u8> as IntoIterator>::Item` normalizes to `u8`.
+    let output = output_ty(&items, "first");
+    let (normalized, is_normalized) = rustc_internal::internal_normalized(tcx, output, &param_env);
+    assert!(is_normalized);
+    let rustc_middle::ty::Adt(_, args) = normalized.kind() else {
+        unreachable!("Expected `Option<u8>`, but found: {normalized}")
+    };
+    assert_eq!(args.type_at(0), tcx.types.u8);
+
+    // `<T as Iterator>::Item` cannot be normalized without the `T: Iterator` bound.
+    let output = output_ty(&items, "next");
+    let (ty, is_normalized) = rustc_internal::internal_normalized(tcx, output, &param_env);
+    assert!(!is_normalized);
+    assert_eq!(ty, rustc_internal::internal(tcx, output));
+    ControlFlow::Continue(())
+}
+
+fn output_ty(items: &CrateItems, name: &str) -> stable_mir::ty::Ty {
+    let item = get_item(items, name).unwrap();
+    item.ty().kind().fn_sig().unwrap().skip_binder().output()
+}
+
+fn get_item<'a>(items: &'a CrateItems, name: &str) -> Option<&'a stable_mir::CrateItem> {
+    items.iter().find(|crate_item| crate_item.name() == name)
+}
+
+/// This test will generate and analyze a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// Then it will create a `StableMir` using custom arguments and then
+/// it will run the compiler.
+fn main() {
+    let path = "internal_normalize_input.rs";
+    generate_input(&path).unwrap();
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.to_string(),
+    ];
+    run_with_tcx!(args, test_normalize).unwrap();
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+    pub fn first(values: Vec<u8>) -> Option<<Vec<u8> as IntoIterator>::Item> {{
+        values.into_iter().next()
+    }}
+
+    pub fn next<T: Iterator>(mut iter: T) -> Option<<T as Iterator>::Item> {{
+        iter.next()
+    }}
+    "#
+    )?;
+    Ok(())
+}
@@ -0,0 +1,111 @@
+//@ run-pass
+//! Test that opaque types in generic arguments can be revealed while converting them to their
+//! internal representation.
+
+//@ ignore-stage1
+//@ ignore-cross-compile
+//@ ignore-remote
+//@ ignore-windows-gnu mingw has troubles with linking https://github.com/rust-lang/rust/pull/116837
+//@ edition: 2021
+
+#![feature(rustc_private)]
+#![feature(assert_matches)]
+#![feature(control_flow_enum)]
+
+#[macro_use]
+extern crate rustc_smir;
+extern crate rustc_driver;
+extern crate rustc_interface;
+extern crate rustc_middle;
+extern crate stable_mir;
+
+use rustc_middle::ty::TyCtxt;
+use rustc_smir::rustc_internal;
+use stable_mir::mir::Body;
+use stable_mir::ty::{AliasKind, GenericArgKind, ParamEnv, Reveal, Ty, TyKind};
+use stable_mir::CrateItems;
+use std::io::Write;
+use std::ops::ControlFlow;
+
+const CRATE_NAME: &str = "input";
+
+fn test_opaque(tcx: TyCtxt<'_>) -> ControlFlow<()> {
+    let items = stable_mir::all_local_items();
+    let opaque = find_opaque(&get_item(&items, "consume").unwrap().body());
+    let arg = GenericArgKind::Type(opaque);
+
+    let param_env = ParamEnv { caller_bounds: vec![], reveal: Reveal::All };
+    let revealed = rustc_internal::internal_checked(tcx, &arg, &param_env).unwrap();
+    assert_eq!(revealed.expect_ty(), tcx.types.u8);
+
+    // Substituting the revealed argument yields the hidden type.
+    let wrapped = get_item(&items, "wrapped").unwrap().ty();
+    let rustc_middle::ty::FnDef(def_id, _) = rustc_internal::internal(tcx, wrapped).kind() else {
+        unreachable!()
+    };
+    let sig = tcx.fn_sig(*def_id).instantiate(tcx, &[revealed]);
+    let rustc_middle::ty::Adt(_, args) = sig.output().skip_binder().kind() else { unreachable!() };
+    assert_eq!(args.type_at(0), tcx.types.u8);
+
+    // Types without opaque types are left untouched.
+    let plain = GenericArgKind::Type(Ty::bool_ty());
+    let internal = rustc_internal::internal_checked(tcx, &plain, &param_env).unwrap();
+    assert_eq!(internal.expect_ty(), tcx.types.bool);
+
+    let user_facing = ParamEnv { caller_bounds: vec![], reveal: Reveal::UserFacing };
+    let error = rustc_internal::internal_checked(tcx, &arg, &user_facing).unwrap_err();
+    assert!(error.to_string().contains("Cannot reveal"), "Unexpected error: {error}");
+    ControlFlow::Continue(())
+}
+
+/// Find the opaque type stored in the locals of the given body.
+fn find_opaque(body: &Body) -> Ty {
+    body.locals()
+        .iter()
+        .find(|local| matches!(local.ty.kind(), TyKind::Alias(AliasKind::Opaque, _)))
+        .unwrap()
+        .ty
+}
+
+fn get_item<'a>(items: &'a CrateItems, name: &str) -> Option<&'a stable_mir::CrateItem> {
+    items.iter().find(|crate_item| crate_item.name() == name)
+}
+
+/// This test will generate and analyze a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// Then it will create a `StableMir` using custom arguments and then
+/// it will run the compiler.
+fn main() {
+    let path = "internal_opaque_input.rs";
+    generate_input(&path).unwrap();
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.to_string(),
+    ];
+    run_with_tcx!(args, test_opaque).unwrap();
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+    pub fn make() -> impl Sized {{
+        5u8
+    }}
+
+    pub fn consume() -> bool {{
+        let value = make();
+        std::mem::size_of_val(&value) == 1
+    }}
+
+    pub fn wrapped<T>(value: T) -> Option<T> {{
+        Some(value)
+    }}
+    "#
+    )?;
+    Ok(())
+}
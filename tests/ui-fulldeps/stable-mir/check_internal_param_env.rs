@@ -0,0 +1,108 @@
+//@ run-pass
+//! Test that a parameter environment built from stable clauses can be used to normalize types.
+
+//@ ignore-stage1
+//@ ignore-cross-compile
+//@ ignore-remote
+//@ ignore-windows-gnu mingw has troubles with linking https://github.com/rust-lang/rust/pull/116837
+//@ edition: 2021
+
+#![feature(rustc_private)]
+#![feature(assert_matches)]
+#![feature(control_flow_enum)]
+
+#[macro_use]
+extern crate rustc_smir;
+extern crate rustc_driver;
+extern crate rustc_interface;
+extern crate rustc_middle;
+extern crate stable_mir;
+
+use rustc_middle::ty::TyCtxt;
+use rustc_smir::rustc_internal;
+use stable_mir::crate_def::CrateDef;
+use stable_mir::ty::{
+    ClauseKind, GenericArgs, ParamEnv, PredicatePolarity, Reveal, RigidTy, TraitPredicate,
+    TraitRef, TyKind,
+};
+use stable_mir::CrateItems;
+use std::io::Write;
+use std::ops::ControlFlow;
+
+const CRATE_NAME: &str = "input";
+
+fn test_param_env(tcx: TyCtxt<'_>) -> ControlFlow<()> {
+    let items = stable_mir::all_local_items();
+    let sig = get_item(&items, "unwrap").unwrap().ty().kind().fn_sig().unwrap().skip_binder();
+    let TyKind::RigidTy(RigidTy::Adt(_, args)) = sig.inputs()[0].kind() else { unreachable!() };
+    let param_ty = *args.0[0].expect_ty();
+
+    // `T: Clone`
+    let clone = stable_mir::all_trait_decls()
+        .into_iter()
+        .find(|def| def.trimmed_name() == "Clone")
+        .unwrap();
+    let clause = ClauseKind::Trait(TraitPredicate {
+        trait_ref: TraitRef::new(clone, param_ty, &GenericArgs(vec![])),
+        polarity: PredicatePolarity::Positive,
+    });
+    let param_env = ParamEnv { caller_bounds: vec![clause], reveal: Reveal::UserFacing };
+    let internal_env = rustc_internal::internal(tcx, &param_env);
+    assert_eq!(internal_env.caller_bounds().len(), 1);
+    assert_eq!(internal_env.reveal(), rustc_middle::traits::Reveal::UserFacing);
+
+    // `<Wrapper<T> as Produce>::Output` normalizes to `T` since `T: Clone` holds.
+    let projection = rustc_internal::internal(tcx, sig.output());
+    assert!(matches!(projection.kind(), rustc_middle::ty::Alias(..)));
+    let normalized = tcx.try_normalize_erasing_regions(internal_env, projection).unwrap();
+    assert_eq!(normalized, rustc_internal::internal(tcx, param_ty));
+    ControlFlow::Continue(())
+}
+
+fn get_item<'a>(items: &'a CrateItems, name: &str) -> Option<&'a stable_mir::CrateItem> {
+    items.iter().find(|crate_item| crate_item.name() == name)
+}
+
+/// This test will generate and analyze a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// Then it will create a `StableMir` using custom arguments and then
+/// it will run the compiler.
+fn main() {
+    let path = "internal_param_env_input.rs";
+    generate_input(&path).unwrap();
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.to_string(),
+    ];
+    run_with_tcx!(args, test_param_env).unwrap();
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+    pub trait Produce {{
+        type Output;
+        fn produce(&self) -> Self::Output;
+    }}
+
+    pub struct Wrapper<T>(pub T);
+
+    impl<T: Clone> Produce for Wrapper<T> {{
+        type Output = T;
+        fn produce(&self) -> T {{
+            self.0.clone()
+        }}
+    }}
+
+    pub fn unwrap<T: Clone>(wrapper: Wrapper<T>) -> <Wrapper<T> as Produce>::Output {{
+        wrapper.produce()
+    }}
+    "#
+    )?;
+    Ok(())
+}
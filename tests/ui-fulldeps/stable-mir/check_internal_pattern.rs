@@ -0,0 +1,106 @@
+//@ run-pass
+//! Test that range endpoints of pattern types are validated when converting them to their
+//! internal representation.
+
+//@ ignore-stage1
+//@ ignore-cross-compile
+//@ ignore-remote
+//@ ignore-windows-gnu mingw has troubles with linking https://github.com/rust-lang/rust/pull/116837
+//@ edition: 2021
+
+#![feature(rustc_private)]
+#![feature(assert_matches)]
+#![feature(control_flow_enum)]
+
+#[macro_use]
+extern crate rustc_smir;
+extern crate rustc_driver;
+extern crate rustc_interface;
+extern crate rustc_middle;
+extern crate stable_mir;
+
+use rustc_middle::ty::TyCtxt;
+use rustc_smir::rustc_internal;
+use stable_mir::crate_def::CrateDef;
+use stable_mir::ty::{Pattern, RigidTy, TyConst, TyKind};
+use stable_mir::CrateItems;
+use std::io::Write;
+use std::ops::ControlFlow;
+
+const CRATE_NAME: &str = "input";
+
+fn test_pattern(tcx: TyCtxt<'_>) -> ControlFlow<()> {
+    let items = stable_mir::all_local_items();
+    let body = get_item(&items, "range").unwrap().body();
+    let TyKind::RigidTy(RigidTy::Pat(ty, pattern)) = body.locals()[1].ty.kind() else {
+        unreachable!("Expected a pattern type, but found: {:?}", body.locals()[1].ty)
+    };
+
+    let checked = rustc_internal::internal_checked(tcx, &pattern, &ty).unwrap();
+    assert_eq!(checked, rustc_internal::internal(tcx, &pattern));
+    let rustc_middle::ty::PatternKind::Range { include_end, .. } = *checked;
+    assert!(include_end);
+
+    // Replace the `u32` start with a `usize` one.
+    let Pattern::Range { end, include_end, .. } = pattern;
+    let start = Some(TyConst::try_from_target_usize(1).unwrap());
+    let mismatched = Pattern::Range { start, end: end.clone(), include_end };
+    let error = rustc_internal::internal_checked(tcx, &mismatched, &ty).unwrap_err();
+    assert!(error.to_string().contains("to have type `u32`"), "Unexpected error: {error}");
+
+    // Replace the start with `<Self as Limit>::MAX`, which cannot be evaluated.
+    let limit = get_item(&items, "Limit::MAX").unwrap();
+    let def_id = rustc_internal::internal(tcx, limit.def_id());
+    let args = rustc_middle::ty::GenericArgs::identity_for_item(tcx, def_id);
+    let uv = rustc_middle::ty::UnevaluatedConst::new(def_id, args);
+    let generic = rustc_middle::ty::Const::new_unevaluated(tcx, uv);
+    assert!(generic.try_eval_scalar_int(tcx, rustc_middle::ty::ParamEnv::reveal_all()).is_none());
+    let start = Some(rustc_internal::stable(generic));
+    let mismatched = Pattern::Range { start, end, include_end };
+    let error = rustc_internal::internal_checked(tcx, &mismatched, &ty).unwrap_err();
+    assert!(error.to_string().contains("but found `u64`"), "Unexpected error: {error}");
+    ControlFlow::Continue(())
+}
+
+fn get_item<'a>(items: &'a CrateItems, name: &str) -> Option<&'a stable_mir::CrateItem> {
+    items.iter().find(|crate_item| crate_item.name() == name)
+}
+
+/// This test will generate and analyze a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// Then it will create a `StableMir` using custom arguments and then
+/// it will run the compiler.
+fn main() {
+    let path = "internal_pattern_input.rs";
+    generate_input(&path).unwrap();
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.to_string(),
+    ];
+    run_with_tcx!(args, test_pattern).unwrap();
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+    #![feature(pattern_types, core_pattern_type, core_pattern_types)]
+    #![allow(incomplete_features)]
+
+    use std::pat::pattern_type;
+
+    pub trait Limit {{
+        const MAX: u64 = 5;
+    }}
+
+    pub fn range(value: pattern_type!(u32 is 1..=5)) -> pattern_type!(u32 is 1..=5) {{
+        value
+    }}
+    "#
+    )?;
+    Ok(())
+}
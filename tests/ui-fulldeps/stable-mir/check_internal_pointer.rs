@@ -0,0 +1,92 @@
+//@ run-pass
+//! Test that pointer types can be built from a stable pointee type and mutability.
+
+//@ ignore-stage1
+//@ ignore-cross-compile
+//@ ignore-remote
+//@ ignore-windows-gnu mingw has troubles with linking https://github.com/rust-lang/rust/pull/116837
+//@ edition: 2021
+
+#![feature(rustc_private)]
+#![feature(assert_matches)]
+#![feature(control_flow_enum)]
+
+#[macro_use]
+extern crate rustc_smir;
+extern crate rustc_driver;
+extern crate rustc_interface;
+extern crate rustc_middle;
+extern crate stable_mir;
+
+use rustc_middle::ty::TyCtxt;
+use rustc_smir::rustc_internal;
+use stable_mir::mir::Mutability;
+use stable_mir::ty::{IntTy, Region, RegionKind, RigidTy, Ty, UintTy};
+use std::io::Write;
+use std::ops::ControlFlow;
+
+const CRATE_NAME: &str = "input";
+
+fn test_pointer(tcx: TyCtxt<'_>) -> ControlFlow<()> {
+    let i32_ty = Ty::signed_ty(IntTy::I32);
+    let pointee = rustc_internal::internal(tcx, (i32_ty, Mutability::Mut));
+    assert_eq!(pointee.ty, tcx.types.i32);
+    assert_eq!(pointee.mutbl, rustc_middle::mir::Mutability::Mut);
+
+    // `*mut i32`
+    let raw_ptr = rustc_middle::ty::Ty::new_ptr(tcx, pointee.ty, pointee.mutbl);
+    assert_eq!(raw_ptr, rustc_internal::internal(tcx, Ty::new_ptr(i32_ty, Mutability::Mut)));
+    assert_eq!(raw_ptr.to_string(), "*mut i32");
+
+    // `*const u8`
+    let u8_ty = Ty::unsigned_ty(UintTy::U8);
+    let const_ptr =
+        rustc_internal::internal_rigid_ty(tcx, &RigidTy::RawPtr(u8_ty, Mutability::Not));
+    assert_eq!(const_ptr, rustc_middle::ty::Ty::new_imm_ptr(tcx, tcx.types.u8));
+    assert_eq!(const_ptr.to_string(), "*const u8");
+
+    // `&'static i32`
+    let pointee = rustc_internal::internal(tcx, (i32_ty, Mutability::Not));
+    let region = Region { kind: RegionKind::ReStatic };
+    let reference = rustc_middle::ty::Ty::new_ref(
+        tcx,
+        rustc_internal::internal(tcx, &region),
+        pointee.ty,
+        pointee.mutbl,
+    );
+    assert_eq!(
+        reference,
+        rustc_internal::internal(tcx, Ty::new_ref(region, i32_ty, Mutability::Not))
+    );
+    ControlFlow::Continue(())
+}
+
+/// This test will generate and analyze a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// Then it will create a `StableMir` using custom arguments and then
+/// it will run the compiler.
+fn main() {
+    let path = "internal_pointer_input.rs";
+    generate_input(&path).unwrap();
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.to_string(),
+    ];
+    run_with_tcx!(args, test_pointer).unwrap();
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+    pub fn deref(ptr: *mut i32) -> i32 {{
+        unsafe {{ *ptr }}
+    }}
+    "#
+    )?;
+    Ok(())
+}
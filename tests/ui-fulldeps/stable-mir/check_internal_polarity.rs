@@ -0,0 +1,87 @@
+//@ run-pass
+//! Test that the polarity of trait predicates and of trait implementations survives the conversion
+//! to their internal representation.
+
+//@ ignore-stage1
+//@ ignore-cross-compile
+//@ ignore-remote
+//@ ignore-windows-gnu mingw has troubles with linking https://github.com/rust-lang/rust/pull/116837
+//@ edition: 2021
+
+#![feature(rustc_private)]
+#![feature(assert_matches)]
+#![feature(control_flow_enum)]
+
+#[macro_use]
+extern crate rustc_smir;
+extern crate rustc_driver;
+extern crate rustc_interface;
+extern crate rustc_middle;
+extern crate stable_mir;
+
+use rustc_middle::ty::TyCtxt;
+use rustc_smir::rustc_internal;
+use stable_mir::crate_def::CrateDef;
+use stable_mir::ty::{ImplPolarity, PredicatePolarity, TraitPredicate};
+use std::io::Write;
+use std::ops::ControlFlow;
+
+const CRATE_NAME: &str = "input";
+
+fn test_polarity(tcx: TyCtxt<'_>) -> ControlFlow<()> {
+    let impls = stable_mir::local_crate().trait_impls();
+    // `impl !Send for Marker`
+    let negative = impls
+        .iter()
+        .find(|def| def.trait_impl().skip_binder().def_id.trimmed_name() == "Send")
+        .unwrap();
+    let trait_ref = negative.trait_impl().skip_binder();
+    let positive = PredicatePolarity::Positive;
+    check_predicate(tcx, TraitPredicate { trait_ref: trait_ref.clone(), polarity: positive });
+    check_predicate(tcx, TraitPredicate { trait_ref, polarity: PredicatePolarity::Negative });
+
+    let def_id = rustc_internal::internal(tcx, negative.def_id());
+    let polarity = rustc_internal::internal(tcx, ImplPolarity::Negative);
+    assert_eq!(polarity, tcx.impl_polarity(def_id));
+    assert_eq!(rustc_internal::stable(polarity), ImplPolarity::Negative);
+    ControlFlow::Continue(())
+}
+
+/// Check that the given trait predicate survives a round trip, including its polarity.
+fn check_predicate(tcx: TyCtxt<'_>, predicate: TraitPredicate) {
+    let internal = rustc_internal::internal(tcx, &predicate);
+    assert_eq!(rustc_internal::stable(internal.polarity), predicate.polarity);
+    assert_eq!(rustc_internal::stable(internal), predicate);
+}
+
+/// This test will generate and analyze a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// Then it will create a `StableMir` using custom arguments and then
+/// it will run the compiler.
+fn main() {
+    let path = "internal_polarity_input.rs";
+    generate_input(&path).unwrap();
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.to_string(),
+    ];
+    run_with_tcx!(args, test_polarity).unwrap();
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+    #![feature(negative_impls)]
+
+    pub struct Marker;
+
+    impl !Send for Marker {{}}
+    "#
+    )?;
+    Ok(())
+}
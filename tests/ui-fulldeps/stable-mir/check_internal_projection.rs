@@ -0,0 +1,144 @@
+//@ run-pass
+//! Test that existential projection terms are validated against the kind of the projected
+//! associated item when converting them to their internal representation, and that existential
+//! trait references are validated against the generic parameters of the trait.
+
+//@ ignore-stage1
+//@ ignore-cross-compile
+//@ ignore-remote
+//@ ignore-windows-gnu mingw has troubles with linking https://github.com/rust-lang/rust/pull/116837
+//@ edition: 2021
+
+#![feature(rustc_private)]
+#![feature(assert_matches)]
+#![feature(control_flow_enum)]
+
+#[macro_use]
+extern crate rustc_smir;
+extern crate rustc_driver;
+extern crate rustc_interface;
+extern crate rustc_middle;
+extern crate stable_mir;
+
+use rustc_middle::ty::TyCtxt;
+use rustc_smir::rustc_internal;
+use stable_mir::crate_def::CrateDef;
+use stable_mir::ty::{
+    Binder, ExistentialPredicate, ExistentialProjection, ExistentialTraitRef, GenericArgKind,
+    GenericArgs, RigidTy, TermKind, TraitDef, Ty, TyConst, TyKind, UintTy,
+};
+use stable_mir::CrateItems;
+use std::io::Write;
+use std::ops::ControlFlow;
+
+const CRATE_NAME: &str = "input";
+
+fn test_projections(tcx: TyCtxt<'_>) -> ControlFlow<()> {
+    let items = stable_mir::all_local_items();
+    let ref_ty = get_item(&items, "iter").unwrap().body().locals()[1].ty;
+    check_assoc_ty(tcx, &ref_ty);
+    check_principal(tcx, &ref_ty);
+
+    let max = items.iter().find(|item| item.name().ends_with("MAX")).unwrap();
+    check_assoc_const(tcx, TraitDef(max.def_id()));
+    ControlFlow::Continue(())
+}
+
+/// Check the `Item = u8` projection of a `dyn Iterator`.
+fn check_assoc_ty(tcx: TyCtxt<'_>, ref_ty: &Ty) {
+    let projection = dyn_predicates(ref_ty)
+        .iter()
+        .find_map(|pred| match &pred.value {
+            ExistentialPredicate::Projection(proj) => Some(proj.clone()),
+            _ => None,
+        })
+        .unwrap();
+    let internal = rustc_internal::internal_checked(tcx, &projection, &()).unwrap();
+    assert_eq!(internal, rustc_internal::internal(tcx, &projection));
+
+    let const_term = ExistentialProjection {
+        term: TermKind::Const(TyConst::try_from_target_usize(3).unwrap()),
+        ..projection
+    };
+    let error = rustc_internal::internal_checked(tcx, &const_term, &()).unwrap_err();
+    assert!(error.to_string().contains("Expected a type term"), "Unexpected error: {error}");
+}
+
+/// Check the `Iterator` principal of a `dyn Iterator`, which must not include `Self`.
+fn check_principal(tcx: TyCtxt<'_>, ref_ty: &Ty) {
+    let principal = dyn_predicates(ref_ty)
+        .iter()
+        .find_map(|pred| match &pred.value {
+            ExistentialPredicate::Trait(trait_ref) => Some(trait_ref.clone()),
+            _ => None,
+        })
+        .unwrap();
+    assert!(principal.generic_args.0.is_empty());
+    let internal = rustc_internal::internal_checked(tcx, &principal, &()).unwrap();
+    assert_eq!(internal, rustc_internal::internal(tcx, &principal));
+
+    let with_self = ExistentialTraitRef {
+        generic_args: GenericArgs(vec![GenericArgKind::Type(Ty::unsigned_ty(UintTy::U8))]),
+        ..principal
+    };
+    let error = rustc_internal::internal_checked(tcx, &with_self, &()).unwrap_err();
+    assert!(error.to_string().contains("do not include `Self`"), "Unexpected error: {error}");
+}
+
+/// Get the predicates of the `dyn` type behind the given reference.
+fn dyn_predicates(ref_ty: &Ty) -> Vec<Binder<ExistentialPredicate>> {
+    let TyKind::RigidTy(RigidTy::Ref(_, dyn_ty, _)) = ref_ty.kind() else { unreachable!() };
+    let TyKind::RigidTy(RigidTy::Dynamic(predicates, _, _)) = dyn_ty.kind() else { unreachable!() };
+    predicates
+}
+
+/// Check a `MAX = 3` projection of the associated constant `Limit::MAX`.
+fn check_assoc_const(tcx: TyCtxt<'_>, max: TraitDef) {
+    let projection = ExistentialProjection {
+        def_id: max,
+        generic_args: GenericArgs(vec![]),
+        term: TermKind::Const(TyConst::try_from_target_usize(3).unwrap()),
+    };
+    let internal = rustc_internal::internal_checked(tcx, &projection, &()).unwrap();
+    assert!(internal.term.as_const().is_some());
+
+    let type_term = ExistentialProjection { term: TermKind::Type(Ty::usize_ty()), ..projection };
+    let error = rustc_internal::internal_checked(tcx, &type_term, &()).unwrap_err();
+    assert!(error.to_string().contains("Expected a constant term"), "Unexpected error: {error}");
+}
+
+fn get_item<'a>(items: &'a CrateItems, name: &str) -> Option<&'a stable_mir::CrateItem> {
+    items.iter().find(|crate_item| crate_item.name() == name)
+}
+
+/// This test will generate and analyze a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// Then it will create a `StableMir` using custom arguments and then
+/// it will run the compiler.
+fn main() {
+    let path = "internal_projection_input.rs";
+    generate_input(&path).unwrap();
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.to_string(),
+    ];
+    run_with_tcx!(args, test_projections).unwrap();
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+    pub trait Limit {{
+        const MAX: usize = 0;
+    }}
+
+    pub fn iter(_iter: &dyn Iterator<Item = u8>) {{}}
+    "#
+    )?;
+    Ok(())
+}
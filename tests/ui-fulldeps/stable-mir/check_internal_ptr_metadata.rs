@@ -0,0 +1,97 @@
+//@ run-pass
+//! Test that the internal metadata type of pointers to a pointee type can be computed.
+
+//@ ignore-stage1
+//@ ignore-cross-compile
+//@ ignore-remote
+//@ ignore-windows-gnu mingw has troubles with linking https://github.com/rust-lang/rust/pull/116837
+//@ edition: 2021
+
+#![feature(rustc_private)]
+#![feature(assert_matches)]
+#![feature(control_flow_enum)]
+
+#[macro_use]
+extern crate rustc_smir;
+extern crate rustc_driver;
+extern crate rustc_interface;
+extern crate rustc_middle;
+extern crate stable_mir;
+
+use rustc_middle::ty::TyCtxt;
+use rustc_smir::rustc_internal;
+use stable_mir::ty::Ty;
+use stable_mir::CrateItems;
+use std::io::Write;
+use std::ops::ControlFlow;
+
+const CRATE_NAME: &str = "input";
+
+fn test_ptr_metadata(tcx: TyCtxt<'_>) -> ControlFlow<()> {
+    let items = stable_mir::all_local_items();
+    let locals = get_item(&items, "metadata").unwrap().body().locals().to_vec();
+    let metadata = |local: usize| {
+        rustc_internal::internal_ptr_metadata_ty(tcx, pointee(locals[local].ty)).unwrap()
+    };
+    // Slices and `str` store their length.
+    assert_eq!(metadata(1), tcx.types.usize);
+    assert_eq!(metadata(2), tcx.types.usize);
+
+    // Trait objects store a pointer to their vtable.
+    let dyn_metadata = metadata(3);
+    let adt_def = dyn_metadata.ty_adt_def().unwrap();
+    assert_eq!(Some(adt_def.did()), tcx.lang_items().dyn_metadata());
+
+    // Sized types have no metadata.
+    assert_eq!(metadata(4), tcx.types.unit);
+
+    // The tail of a generic pointee is unknown.
+    let locals = get_item(&items, "generic").unwrap().body().locals().to_vec();
+    let error = rustc_internal::internal_ptr_metadata_ty(tcx, pointee(locals[1].ty)).unwrap_err();
+    assert!(error.to_string().contains("Cannot determine"), "Unexpected error: {error}");
+    ControlFlow::Continue(())
+}
+
+fn pointee(ty: Ty) -> Ty {
+    ty.kind().builtin_deref(true).unwrap().ty
+}
+
+fn get_item<'a>(items: &'a CrateItems, name: &str) -> Option<&'a stable_mir::CrateItem> {
+    items.iter().find(|crate_item| crate_item.name() == name)
+}
+
+/// This test will generate and analyze a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// Then it will create a `StableMir` using custom arguments and then
+/// it will run the compiler.
+fn main() {
+    let path = "internal_ptr_metadata_input.rs";
+    generate_input(&path).unwrap();
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.to_string(),
+    ];
+    run_with_tcx!(args, test_ptr_metadata).unwrap();
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+    pub trait Shape {{}}
+
+    pub fn metadata(bytes: &[u8], text: &str, _shape: &dyn Shape, value: &u32) -> usize {{
+        bytes.len() + text.len() + *value as usize
+    }}
+
+    pub fn generic<T: ?Sized>(value: &T) -> &T {{
+        value
+    }}
+    "#
+    )?;
+    Ok(())
+}
@@ -0,0 +1,79 @@
+//@ run-pass
+//! Test that plain enums can be converted to their internal representation without initializing
+//! StableMIR.
+
+//@ ignore-stage1
+//@ ignore-cross-compile
+//@ ignore-remote
+//@ ignore-windows-gnu mingw has troubles with linking https://github.com/rust-lang/rust/pull/116837
+//@ edition: 2021
+
+#![feature(rustc_private)]
+#![feature(assert_matches)]
+#![feature(control_flow_enum)]
+
+#[macro_use]
+extern crate rustc_smir;
+extern crate rustc_driver;
+extern crate rustc_interface;
+extern crate rustc_middle;
+extern crate stable_mir;
+
+use rustc_middle::ty::TyCtxt;
+use rustc_smir::rustc_internal::{self, IntoInternal};
+use stable_mir::abi::{IntegerLength, Primitive};
+use stable_mir::mir::{BinOp, BorrowKind, MutBorrowKind};
+use stable_mir::ty::{Abi, ClosureKind, IntTy};
+use std::io::Write;
+use std::ops::ControlFlow;
+
+const CRATE_NAME: &str = "input";
+
+fn test_pure(tcx: TyCtxt<'_>) -> ControlFlow<()> {
+    // The conversions match the ones that have access to the type context.
+    assert_eq!(rustc_internal::internal(tcx, BinOp::Add), BinOp::Add.to_internal());
+    assert_eq!(rustc_internal::internal(tcx, IntTy::I32), IntTy::I32.to_internal());
+    let abi = Abi::C { unwind: true };
+    assert_eq!(rustc_internal::internal(tcx, &abi), abi.to_internal());
+    let primitive = Primitive::Int { length: IntegerLength::I32, signed: true };
+    assert_eq!(rustc_internal::internal(tcx, primitive), primitive.to_internal());
+    ControlFlow::Continue(())
+}
+
+/// This test will generate and analyze a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// Then it will create a `StableMir` using custom arguments and then
+/// it will run the compiler.
+fn main() {
+    // No tables are needed before the compiler runs.
+    assert_eq!(BinOp::Add.to_internal(), rustc_middle::mir::BinOp::Add);
+    assert_eq!(IntTy::I32.to_internal(), rustc_middle::ty::IntTy::I32);
+    assert_eq!(ClosureKind::FnMut.to_internal(), rustc_middle::ty::ClosureKind::FnMut);
+    let borrow = BorrowKind::Mut { kind: MutBorrowKind::TwoPhaseBorrow };
+    let expected = rustc_middle::mir::MutBorrowKind::TwoPhaseBorrow;
+    assert_eq!(borrow.to_internal(), rustc_middle::mir::BorrowKind::Mut { kind: expected });
+
+    let path = "internal_pure_input.rs";
+    generate_input(&path).unwrap();
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.to_string(),
+    ];
+    run_with_tcx!(args, test_pure).unwrap();
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+    pub fn add(left: i32, right: i32) -> i32 {{
+        left + right
+    }}
+    "#
+    )?;
+    Ok(())
+}
@@ -0,0 +1,108 @@
+//@ run-pass
+//! Test that scalars can be read from a stable allocation into their internal representation.
+
+//@ ignore-stage1
+//@ ignore-cross-compile
+//@ ignore-remote
+//@ ignore-windows-gnu mingw has troubles with linking https://github.com/rust-lang/rust/pull/116837
+//@ edition: 2021
+
+#![feature(rustc_private)]
+#![feature(assert_matches)]
+#![feature(control_flow_enum)]
+
+#[macro_use]
+extern crate rustc_smir;
+extern crate rustc_driver;
+extern crate rustc_interface;
+extern crate rustc_middle;
+extern crate stable_mir;
+
+use rustc_middle::mir::interpret::Scalar;
+use rustc_middle::ty::TyCtxt;
+use rustc_smir::rustc_internal;
+use stable_mir::mir::mono::StaticDef;
+use stable_mir::mir::Mutability;
+use stable_mir::ty::{Allocation, ProvenanceMap};
+use stable_mir::CrateItems;
+use std::io::Write;
+use std::ops::ControlFlow;
+
+const CRATE_NAME: &str = "input";
+
+fn test_read_scalar(tcx: TyCtxt<'_>) -> ControlFlow<()> {
+    let bytes = [7u32, 42u32].iter().flat_map(|value| value.to_ne_bytes()).map(Some).collect();
+    let mut alloc = Allocation {
+        bytes,
+        provenance: ProvenanceMap { ptrs: vec![] },
+        align: 4,
+        mutability: Mutability::Not,
+    };
+    let scalar = rustc_internal::internal_read_scalar(tcx, &alloc, 4, 4).unwrap();
+    assert_eq!(scalar, Scalar::from_u32(42));
+
+    let error = rustc_internal::internal_read_scalar(tcx, &alloc, 6, 4).unwrap_err();
+    assert!(error.to_string().contains("only has 8 bytes"), "Unexpected error: {error}");
+
+    let range = rustc_internal::internal_alloc_range(&alloc, 2, 6).unwrap();
+    assert_eq!((range.start.bytes(), range.size.bytes()), (2, 6));
+    let error = rustc_internal::internal_alloc_range(&alloc, 2, 7).unwrap_err();
+    assert!(error.to_string().contains("out of bounds"), "Unexpected error: {error}");
+    let error = rustc_internal::internal_alloc_range(&alloc, usize::MAX, 1).unwrap_err();
+    assert!(error.to_string().contains("out of bounds"), "Unexpected error: {error}");
+
+    alloc.bytes[5] = None;
+    let error = rustc_internal::internal_read_scalar(tcx, &alloc, 4, 4).unwrap_err();
+    assert!(error.to_string().contains("uninitialized"), "Unexpected error: {error}");
+
+    check_pointer(tcx);
+    ControlFlow::Continue(())
+}
+
+/// Check that reading a pointer keeps its provenance.
+fn check_pointer(tcx: TyCtxt<'_>) {
+    let items = stable_mir::all_local_items();
+    let def = StaticDef::try_from(*get_item(&items, "REF").unwrap()).unwrap();
+    let alloc = def.eval_initializer().unwrap();
+    let (_, prov) = alloc.provenance.ptrs[0];
+
+    let ptr_size = tcx.data_layout.pointer_size.bytes_usize();
+    let scalar = rustc_internal::internal_read_scalar(tcx, &alloc, 0, ptr_size).unwrap();
+    let Scalar::Ptr(ptr, _) = scalar else { unreachable!("Expected a pointer: {scalar:?}") };
+    assert_eq!(ptr.provenance.alloc_id(), rustc_internal::internal(tcx, prov.0));
+
+    let error = rustc_internal::internal_read_scalar(tcx, &alloc, 0, 1).unwrap_err();
+    assert!(error.to_string().contains("partially overlaps"), "Unexpected error: {error}");
+}
+
+fn get_item<'a>(items: &'a CrateItems, name: &str) -> Option<&'a stable_mir::CrateItem> {
+    items.iter().find(|crate_item| crate_item.name() == name)
+}
+
+/// This test will generate and analyze a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// Then it will create a `StableMir` using custom arguments and then
+/// it will run the compiler.
+fn main() {
+    let path = "internal_read_scalar_input.rs";
+    generate_input(&path).unwrap();
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.to_string(),
+    ];
+    run_with_tcx!(args, test_read_scalar).unwrap();
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+    pub static REF: &u32 = &7;
+    "#
+    )?;
+    Ok(())
+}
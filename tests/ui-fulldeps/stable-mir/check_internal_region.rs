@@ -0,0 +1,163 @@
+//@ run-pass
+//! Test that regions and their names are preserved when converting them to their internal
+//! representation.
+
+//@ ignore-stage1
+//@ ignore-cross-compile
+//@ ignore-remote
+//@ ignore-windows-gnu mingw has troubles with linking https://github.com/rust-lang/rust/pull/116837
+//@ edition: 2021
+
+#![feature(rustc_private)]
+#![feature(assert_matches)]
+#![feature(control_flow_enum)]
+
+#[macro_use]
+extern crate rustc_smir;
+extern crate rustc_driver;
+extern crate rustc_interface;
+extern crate rustc_middle;
+extern crate stable_mir;
+
+use rustc_middle::ty::TyCtxt;
+use rustc_smir::rustc_internal;
+use stable_mir::ty::{BoundRegionKind, Region, RegionKind, RigidTy, Ty, TyKind};
+use stable_mir::CrateItems;
+use std::io::Write;
+use std::ops::ControlFlow;
+
+const CRATE_NAME: &str = "input";
+
+fn test_regions(tcx: TyCtxt<'_>) -> ControlFlow<()> {
+    let items = stable_mir::all_local_items();
+
+    // `'static`
+    let output = output_ty(get_item(&items, "fixed").unwrap());
+    let region = ref_region(output);
+    assert_eq!(region.kind, RegionKind::ReStatic);
+    assert!(rustc_internal::internal(tcx, &region).is_static());
+    check_region(tcx, output, &region);
+
+    // Early-bound `'a`
+    let output = output_ty(get_item(&items, "early").unwrap());
+    let region = ref_region(output);
+    assert!(matches!(&region.kind, RegionKind::ReEarlyParam(param) if param.name == "'a"));
+    check_region(tcx, output, &region);
+
+    // `'a` bound by the signature binder
+    let sig = get_item(&items, "late").unwrap().ty().kind().fn_sig().unwrap();
+    let input = sig.value.inputs()[0];
+    let region = ref_region(input);
+    assert!(matches!(&region.kind, RegionKind::ReBound(0, bound) if bound.var == 0));
+    check_region(tcx, input, &region);
+    check_bound_name(tcx, &region);
+
+    check_nested(tcx, get_item(&items, "nested").unwrap());
+    ControlFlow::Continue(())
+}
+
+/// Check that a region bound by an outer binder keeps its de Bruijn index inside a nested binder.
+fn check_nested(tcx: TyCtxt<'_>, item: &stable_mir::CrateItem) {
+    // `for<'a> fn(for<'b> fn(&'a &'b u8))`
+    let outer = item.ty().kind().fn_sig().unwrap().skip_binder().inputs()[0];
+    let outer_sig = outer.kind().fn_sig().unwrap();
+    // `for<'b> fn(&'a &'b u8)`
+    let inner_sig = outer_sig.value.inputs()[0].kind().fn_sig().unwrap();
+    // `&'a &'b u8`
+    let arg = inner_sig.value.inputs()[0];
+    let TyKind::RigidTy(RigidTy::Ref(region, pointee, mutability)) = arg.kind() else {
+        unreachable!()
+    };
+    assert!(matches!(&region.kind, RegionKind::ReBound(1, bound) if bound.var == 0));
+    let rustc_middle::ty::ReBound(debruijn, _) = *rustc_internal::internal(tcx, &region) else {
+        unreachable!()
+    };
+    assert_eq!(debruijn.as_u32(), 1);
+    check_region(tcx, arg, &region);
+
+    let rebuilt = Ty::new_ref(region, pointee, mutability);
+    assert_eq!(rustc_internal::internal(tcx, rebuilt), rustc_internal::internal(tcx, arg));
+    let internal_sig = rustc_internal::internal_checked(tcx, &outer_sig, &()).unwrap();
+    assert_eq!(internal_sig, rustc_internal::internal(tcx, outer).fn_sig(tcx));
+}
+
+/// Check that the name of a named bound region interns to the same symbol as the original one.
+fn check_bound_name(tcx: TyCtxt<'_>, region: &Region) {
+    let RegionKind::ReBound(_, bound) = &region.kind else { unreachable!() };
+    let BoundRegionKind::BrNamed(_, name) = &bound.kind else {
+        unreachable!("Expected a named region, but found: {:?}", bound.kind)
+    };
+    let symbol = rustc_internal::internal(tcx, name);
+    assert_eq!(symbol.as_str(), "'a");
+    let rustc_middle::ty::ReBound(_, expected) = *rustc_internal::internal(tcx, region) else {
+        unreachable!()
+    };
+    assert_eq!(Some(symbol), expected.kind.get_name());
+
+    // The bound region and its kind can also be converted on their own.
+    let standalone = rustc_internal::internal(tcx, bound);
+    assert_eq!(standalone, expected);
+    assert_eq!(rustc_internal::internal(tcx, &bound.kind).get_name(), Some(symbol));
+}
+
+/// Check that the region converts to the same region as the one in the internal reference type.
+fn check_region(tcx: TyCtxt<'_>, ref_ty: Ty, region: &Region) {
+    let internal_ty = rustc_internal::internal(tcx, ref_ty);
+    let rustc_middle::ty::Ref(expected, _, _) = *internal_ty.kind() else { unreachable!() };
+    assert_eq!(rustc_internal::internal(tcx, region), expected);
+}
+
+fn output_ty(item: &stable_mir::CrateItem) -> Ty {
+    item.ty().kind().fn_sig().unwrap().skip_binder().output()
+}
+
+fn ref_region(ty: Ty) -> Region {
+    let TyKind::RigidTy(RigidTy::Ref(region, _, _)) = ty.kind() else {
+        unreachable!("Expected a reference, but found: {ty:?}")
+    };
+    region
+}
+
+fn get_item<'a>(items: &'a CrateItems, name: &str) -> Option<&'a stable_mir::CrateItem> {
+    items.iter().find(|crate_item| crate_item.name() == name)
+}
+
+/// This test will generate and analyze a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// Then it will create a `StableMir` using custom arguments and then
+/// it will run the compiler.
+fn main() {
+    let path = "internal_region_input.rs";
+    generate_input(&path).unwrap();
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.to_string(),
+    ];
+    run_with_tcx!(args, test_regions).unwrap();
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+    pub fn fixed() -> &'static str {{
+        "fixed"
+    }}
+
+    pub fn early<'a, T: 'a>(value: &'a T) -> &'a T {{
+        value
+    }}
+
+    pub fn late<'a>(value: &'a u8) -> u8 {{
+        *value
+    }}
+
+    pub fn nested(_callback: for<'a> fn(for<'b> fn(&'a &'b u8))) {{}}
+    "#
+    )?;
+    Ok(())
+}
@@ -0,0 +1,139 @@
+//@ run-pass
+//! Test that ADT kinds, representation options, sizes and alignments can be converted to their
+//! internal representation.
+
+//@ ignore-stage1
+//@ ignore-cross-compile
+//@ ignore-remote
+//@ ignore-windows-gnu mingw has troubles with linking https://github.com/rust-lang/rust/pull/116837
+//@ edition: 2021
+
+#![feature(rustc_private)]
+#![feature(assert_matches)]
+#![feature(control_flow_enum)]
+
+#[macro_use]
+extern crate rustc_smir;
+extern crate rustc_driver;
+extern crate rustc_interface;
+extern crate rustc_middle;
+extern crate stable_mir;
+
+use rustc_middle::ty::TyCtxt;
+use rustc_smir::rustc_internal;
+use stable_mir::abi::{IntegerLength, IntegerType};
+use stable_mir::target::MachineSize;
+use stable_mir::ty::{AdtDef, AdtKind, RigidTy, TyKind};
+use stable_mir::CrateItems;
+use std::io::Write;
+use std::ops::ControlFlow;
+
+const CRATE_NAME: &str = "input";
+
+fn test_repr(tcx: TyCtxt<'_>) -> ControlFlow<()> {
+    let items = stable_mir::all_local_items();
+    let body = get_item(&items, "convert").unwrap().body();
+
+    let packed = adt_def(&body.locals()[1].ty.kind());
+    assert_eq!(packed.kind(), AdtKind::Struct);
+    assert_eq!(rustc_internal::internal(tcx, packed.kind()), rustc_middle::ty::AdtKind::Struct);
+    let repr = packed.repr();
+    assert!(repr.flags.is_c);
+    assert_eq!(repr.pack, Some(2));
+    check_repr(tcx, packed);
+
+    let tagged = adt_def(&body.locals()[0].ty.kind());
+    assert_eq!(rustc_internal::internal(tcx, tagged.kind()), rustc_middle::ty::AdtKind::Enum);
+    let repr = tagged.repr();
+    assert_eq!(repr.int, Some(IntegerType::Fixed { length: IntegerLength::I16, is_signed: false }));
+    assert_eq!(repr.align, Some(8));
+    check_repr(tcx, tagged);
+    check_primitives(tcx);
+    ControlFlow::Continue(())
+}
+
+/// Check that sizes and alignments are converted, and that invalid alignments are rejected.
+fn check_primitives(tcx: TyCtxt<'_>) {
+    let internal = rustc_internal::internal_align(8).unwrap();
+    assert_eq!(internal.bytes(), 8);
+
+    let size = rustc_internal::internal(tcx, MachineSize::from_bits(128));
+    assert_eq!(size.bytes(), 16);
+
+    let error = rustc_internal::internal_align(3).unwrap_err();
+    assert!(error.to_string().contains("not a power of 2"), "Unexpected error: {error}");
+}
+
+/// Check that the converted representation options match the ones computed by the compiler.
+fn check_repr(tcx: TyCtxt<'_>, def: AdtDef) {
+    let repr = rustc_internal::internal_checked(tcx, def.repr(), &()).unwrap();
+    let expected = rustc_internal::internal(tcx, def).repr();
+    assert_eq!(repr.int, expected.int);
+    assert_eq!(repr.align, expected.align);
+    assert_eq!(repr.pack, expected.pack);
+    assert_eq!(repr.flags, expected.flags);
+
+    // Neither the alignment nor the packing can be an invalid alignment.
+    let mut invalid = def.repr();
+    invalid.align = Some(3);
+    let error = rustc_internal::internal_checked(tcx, &invalid, &()).unwrap_err();
+    assert!(error.to_string().contains("not a power of 2"), "Unexpected error: {error}");
+
+    let mut invalid = def.repr();
+    invalid.pack = Some(1 << 30);
+    let error = rustc_internal::internal_checked(tcx, &invalid, &()).unwrap_err();
+    assert!(error.to_string().contains("too large"), "Unexpected error: {error}");
+}
+
+fn adt_def(kind: &TyKind) -> AdtDef {
+    let TyKind::RigidTy(RigidTy::Adt(def, _)) = kind else {
+        unreachable!("Expected an ADT, but found: {kind:?}")
+    };
+    *def
+}
+
+fn get_item<'a>(items: &'a CrateItems, name: &str) -> Option<&'a stable_mir::CrateItem> {
+    items.iter().find(|crate_item| crate_item.name() == name)
+}
+
+/// This test will generate and analyze a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// Then it will create a `StableMir` using custom arguments and then
+/// it will run the compiler.
+fn main() {
+    let path = "internal_repr_input.rs";
+    generate_input(&path).unwrap();
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.to_string(),
+    ];
+    run_with_tcx!(args, test_repr).unwrap();
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+    #[repr(C, packed(2))]
+    pub struct Packed {{
+        pub tag: u8,
+        pub value: u32,
+    }}
+
+    #[repr(u16, align(8))]
+    pub enum Tagged {{
+        Small,
+        Large,
+    }}
+
+    pub fn convert(packed: Packed) -> Tagged {{
+        if packed.tag == 0 {{ Tagged::Small }} else {{ Tagged::Large }}
+    }}
+    "#
+    )?;
+    Ok(())
+}
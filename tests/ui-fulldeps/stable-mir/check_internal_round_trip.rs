@@ -0,0 +1,300 @@
+//@ run-pass
+//! Test that converting stable constructs to their internal representation and back yields the
+//! original construct.
+//!
+//! Each category of construct is checked separately over every body of the input crate.
+//! Types and constants are interned, so converting them only looks them up in the tables. They are
+//! rebuilt from their kind instead, and compared against the original.
+
+//@ ignore-stage1
+//@ ignore-cross-compile
+//@ ignore-remote
+//@ ignore-windows-gnu mingw has troubles with linking https://github.com/rust-lang/rust/pull/116837
+//@ edition: 2021
+
+#![feature(rustc_private)]
+#![feature(assert_matches)]
+#![feature(control_flow_enum)]
+
+#[macro_use]
+extern crate rustc_smir;
+extern crate rustc_driver;
+extern crate rustc_interface;
+extern crate rustc_middle;
+extern crate stable_mir;
+
+use rustc_middle::mir::ConstValue;
+use rustc_middle::ty::{TyCtxt, TypeVisitableExt};
+use rustc_smir::rustc_internal;
+use stable_mir::mir::visit::{Location, PlaceContext};
+use stable_mir::mir::{Body, MirVisitor, Place};
+use stable_mir::ty::{
+    Allocation, ConstantKind, MirConst, RigidTy, Span, Ty, TyConst, TyConstKind, TyKind,
+};
+use std::io::Write;
+use std::ops::ControlFlow;
+
+const CRATE_NAME: &str = "input";
+
+fn test_round_trip(tcx: TyCtxt<'_>) -> ControlFlow<()> {
+    let items = stable_mir::all_local_items();
+    assert!(!items.is_empty());
+    let mut regions = 0;
+    for item in &items {
+        let collector = Collector::collect(&item.body());
+        check_types(tcx, &collector.tys);
+        regions += check_regions(tcx, item);
+        check_consts(tcx, &collector.ty_consts, &collector.mir_consts);
+        check_body(tcx, &collector);
+        check_indexed(tcx, &collector);
+    }
+    // `&'static str` and the late-bound `&'a [u8; N]` and `&mut Pair<T>`.
+    assert_eq!(regions, 3);
+    ControlFlow::Continue(())
+}
+
+/// Rebuilding a type from its kind must produce the original type.
+fn check_types(tcx: TyCtxt<'_>, tys: &[Ty]) {
+    for ty in tys {
+        let rebuilt = rustc_internal::internal(tcx, &ty.kind());
+        assert_eq!(rebuilt, rustc_internal::internal(tcx, ty), "Failed to rebuild `{ty:?}`");
+        assert_eq!(rustc_internal::stable(rebuilt), *ty);
+    }
+}
+
+/// Check that the regions of the references in the signature of a function are not erased, and
+/// return how many were checked. Regions in bodies are always erased, so only signatures are used.
+fn check_regions(tcx: TyCtxt<'_>, item: &stable_mir::CrateItem) -> usize {
+    let Some(sig) = item.ty().kind().fn_sig() else { return 0 };
+    let mut checked = 0;
+    for ty in &sig.value.inputs_and_output {
+        let TyKind::RigidTy(RigidTy::Ref(region, _, _)) = ty.kind() else { continue };
+        let internal = rustc_internal::internal(tcx, ty);
+        let rustc_middle::ty::Ref(expected, _, _) = *internal.kind() else { unreachable!() };
+        assert!(!expected.is_erased());
+        assert_eq!(rustc_internal::internal(tcx, &region), expected);
+        assert_eq!(rustc_internal::internal(tcx, &ty.kind()), internal);
+        checked += 1;
+    }
+    checked
+}
+
+/// Rebuild each constant from its kind, and compare it against the original constant.
+///
+/// Only the values of scalars without provenance are compared, since other values are stored in
+/// allocations that have no internal conversion.
+fn check_consts(tcx: TyCtxt<'_>, ty_consts: &[TyConst], mir_consts: &[MirConst]) {
+    for ct in ty_consts {
+        let internal = rustc_internal::internal(tcx, ct);
+        match ct.kind() {
+            TyConstKind::Param(param) => {
+                let rustc_middle::ty::ConstKind::Param(expected) = internal.kind() else {
+                    unreachable!("Expected a const parameter, but found: {internal:?}")
+                };
+                assert_eq!((expected.index, expected.name.as_str()), (param.index, &*param.name));
+            }
+            TyConstKind::Unevaluated(..) => {
+                let rebuilt = rustc_internal::internal_checked(tcx, ct.kind(), &()).unwrap();
+                assert_eq!(rebuilt, internal);
+            }
+            TyConstKind::Value(ty, alloc) => {
+                let rustc_middle::ty::ConstKind::Value(expected_ty, _) = internal.kind() else {
+                    unreachable!("Expected a value, but found: {internal:?}")
+                };
+                assert_eq!(rustc_internal::internal(tcx, ty), expected_ty);
+                if let (Some(bits), Some(scalar)) = (scalar_bits(alloc), internal.try_to_scalar()) {
+                    assert_eq!(scalar.try_to_scalar_int().unwrap().to_bits_unchecked(), bits);
+                }
+            }
+            TyConstKind::Bound(..) | TyConstKind::ZSTValue(_) => {}
+        }
+    }
+    for ct in mir_consts {
+        let (internal, ty) = rustc_internal::internal_mir_const_with_ty(tcx, ct);
+        assert_eq!(ty, rustc_internal::internal(tcx, ct.ty()));
+        match (ct.kind(), internal) {
+            (ConstantKind::Ty(ty_const), rustc_middle::mir::Const::Ty(_, expected)) => {
+                assert_eq!(rustc_internal::internal(tcx, ty_const), expected);
+            }
+            (ConstantKind::ZeroSized, rustc_middle::mir::Const::Val(value, _)) => {
+                assert_eq!(value, ConstValue::ZeroSized);
+            }
+            (ConstantKind::Allocated(alloc), rustc_middle::mir::Const::Val(value, _)) => {
+                if let (Some(bits), ConstValue::Scalar(scalar)) = (scalar_bits(alloc), value) {
+                    assert_eq!(scalar.try_to_scalar_int().unwrap().to_bits_unchecked(), bits);
+                }
+            }
+            (ConstantKind::Unevaluated(uv), rustc_middle::mir::Const::Unevaluated(expected, _)) => {
+                assert_eq!(rustc_internal::internal(tcx, uv.def.0), expected.def);
+                assert_eq!(rustc_internal::internal(tcx, &uv.args), expected.args);
+                assert_eq!(
+                    uv.promoted.map(|promoted| promoted as usize),
+                    expected.promoted.map(|promoted| promoted.as_usize())
+                );
+            }
+            (ConstantKind::Param(param), rustc_middle::mir::Const::Ty(_, expected)) => {
+                let rustc_middle::ty::ConstKind::Param(expected) = expected.kind() else {
+                    unreachable!("Expected a const parameter, but found: {expected:?}")
+                };
+                assert_eq!(expected.index, param.index);
+            }
+            (kind, internal) => panic!("Unexpected constant `{internal:?}` for `{kind:?}`"),
+        }
+    }
+}
+
+/// The integer stored in the allocation, if it is a non-empty scalar without provenance.
+fn scalar_bits(alloc: &Allocation) -> Option<u128> {
+    if alloc.bytes.is_empty() || !alloc.provenance.ptrs.is_empty() {
+        return None;
+    }
+    alloc.read_uint().ok()
+}
+
+/// Layouts and allocations are only referred to by ids backed by the tables, so they must map back
+/// to themselves, and describe the same layout or allocation.
+fn check_indexed(tcx: TyCtxt<'_>, collector: &Collector) {
+    let monomorphic =
+        collector.tys.iter().filter(|ty| !rustc_internal::internal(tcx, ty).has_param());
+    for layout in monomorphic.filter_map(|ty| ty.layout().ok()) {
+        let internal = rustc_internal::internal(tcx, layout);
+        assert_eq!(rustc_internal::stable(internal), layout);
+        let shape = layout.shape();
+        assert_eq!(internal.size().bytes_usize(), shape.size);
+        assert_eq!(internal.align().abi.bytes(), shape.abi_align);
+    }
+    for ct in &collector.mir_consts {
+        if let ConstantKind::Allocated(alloc) = ct.kind() {
+            for (_, prov) in &alloc.provenance.ptrs {
+                let internal = rustc_internal::internal(tcx, prov.0);
+                assert!(tcx.try_get_global_alloc(internal).is_some());
+                assert_eq!(rustc_internal::stable(internal), prov.0);
+            }
+        }
+    }
+}
+
+/// There is no internal conversion for a whole body, so check every place and span it contains.
+///
+/// Places are rebuilt from their local and projections. Spans are only looked up in the tables, so
+/// check that they still point to the same source lines instead of being collapsed.
+///
+/// The statements, terminators, local declarations, debug info and argument count of the body have
+/// no internal conversion, so they are not checked.
+fn check_body(tcx: TyCtxt<'_>, collector: &Collector) {
+    for place in &collector.places {
+        let internal = rustc_internal::internal(tcx, place);
+        assert_eq!(&rustc_internal::stable(&internal), place);
+    }
+    let source_map = tcx.sess.source_map();
+    for span in &collector.spans {
+        let internal = rustc_internal::internal(tcx, span);
+        assert!(!internal.is_dummy());
+        let lines = span.get_lines();
+        assert_eq!(source_map.lookup_char_pos(internal.lo()).line, lines.start_line);
+        assert_eq!(source_map.lookup_char_pos(internal.hi()).line, lines.end_line);
+        assert_eq!(&rustc_internal::stable(internal), span);
+    }
+}
+
+#[derive(Default)]
+struct Collector {
+    tys: Vec<Ty>,
+    ty_consts: Vec<TyConst>,
+    mir_consts: Vec<MirConst>,
+    places: Vec<Place>,
+    spans: Vec<Span>,
+}
+
+impl Collector {
+    fn collect(body: &Body) -> Collector {
+        let mut collector = Collector::default();
+        collector.visit_body(body);
+        collector
+    }
+}
+
+impl MirVisitor for Collector {
+    fn visit_ty(&mut self, ty: &Ty, _location: Location) {
+        if let TyKind::RigidTy(RigidTy::Array(_, len)) = ty.kind() {
+            self.ty_consts.push(len);
+        }
+        self.tys.push(*ty);
+        self.super_ty(ty)
+    }
+
+    fn visit_ty_const(&mut self, constant: &TyConst, _location: Location) {
+        self.ty_consts.push(constant.clone());
+        self.super_ty_const(constant)
+    }
+
+    fn visit_mir_const(&mut self, constant: &MirConst, location: Location) {
+        self.mir_consts.push(constant.clone());
+        self.super_mir_const(constant, location)
+    }
+
+    fn visit_place(&mut self, place: &Place, ptx: PlaceContext, location: Location) {
+        self.places.push(place.clone());
+        self.super_place(place, ptx, location)
+    }
+
+    fn visit_span(&mut self, span: &Span) {
+        self.spans.push(*span);
+        self.super_span(span)
+    }
+}
+
+/// This test will generate and analyze a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// Then it will create a `StableMir` using custom arguments and then
+/// it will run the compiler.
+fn main() {
+    let path = "internal_round_trip_input.rs";
+    generate_input(&path).unwrap();
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.to_string(),
+    ];
+    run_with_tcx!(args, test_round_trip).unwrap();
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+    pub struct Pair<T> {{
+        pub first: T,
+        pub second: T,
+    }}
+
+    pub const SIZE: usize = 4;
+
+    pub fn sum(values: [u32; SIZE]) -> u32 {{
+        let mut total = 0;
+        for value in values {{
+            total += value;
+        }}
+        total
+    }}
+
+    pub fn swap<T: Copy>(pair: &mut Pair<T>) {{
+        let first = pair.first;
+        pair.first = pair.second;
+        pair.second = first;
+    }}
+
+    pub fn first<'a, const N: usize>(values: &'a [u8; N]) -> Option<&'a u8> {{
+        values.first()
+    }}
+
+    pub fn greet() -> &'static str {{
+        "hello"
+    }}
+    "#
+    )?;
+    Ok(())
+}
@@ -0,0 +1,96 @@
+//@ run-pass
+//! Test that scalars are converted to their internal representation with their valid range, and
+//! that the valid range is validated against the size of the primitive.
+
+//@ ignore-stage1
+//@ ignore-cross-compile
+//@ ignore-remote
+//@ ignore-windows-gnu mingw has troubles with linking https://github.com/rust-lang/rust/pull/116837
+//@ edition: 2021
+
+#![feature(rustc_private)]
+#![feature(assert_matches)]
+#![feature(control_flow_enum)]
+
+#[macro_use]
+extern crate rustc_smir;
+extern crate rustc_driver;
+extern crate rustc_interface;
+extern crate rustc_middle;
+extern crate rustc_target;
+extern crate stable_mir;
+
+use rustc_middle::ty::TyCtxt;
+use rustc_smir::rustc_internal;
+use stable_mir::abi::{IntegerLength, Primitive, Scalar, ValueAbi, WrappingRange};
+use stable_mir::CrateItems;
+use std::io::Write;
+use std::ops::ControlFlow;
+
+const CRATE_NAME: &str = "input";
+
+fn test_scalar(tcx: TyCtxt<'_>) -> ControlFlow<()> {
+    let items = stable_mir::all_local_items();
+    let body = get_item(&items, "get").unwrap().body();
+    let ty = body.locals()[1].ty;
+    let abi = ty.layout().unwrap().shape().abi;
+    let ValueAbi::Scalar(scalar) = abi else {
+        unreachable!("Expected a scalar, but found: {abi:?}")
+    };
+    let Scalar::Initialized { value: Primitive::Pointer(_), valid_range } = scalar else {
+        unreachable!("Expected an initialized pointer, but found: {scalar:?}")
+    };
+    // The null pointer is the niche of `Option<&u8>`.
+    assert_eq!(valid_range.start, 1);
+
+    let internal = rustc_internal::internal_checked(tcx, &scalar, &()).unwrap();
+    assert_eq!(internal, rustc_internal::internal(tcx, &scalar));
+    let param_env = rustc_middle::ty::ParamEnv::reveal_all();
+    let layout = tcx.layout_of(param_env.and(rustc_internal::internal(tcx, ty))).unwrap();
+    let rustc_target::abi::Abi::Scalar(expected) = layout.abi else { unreachable!() };
+    assert_eq!(internal, expected);
+    assert!(!internal.valid_range(&tcx).contains(0));
+
+    // A `u8` can never be 256.
+    let invalid = Scalar::Initialized {
+        value: Primitive::Int { length: IntegerLength::I8, signed: false },
+        valid_range: WrappingRange { start: 0, end: 256 },
+    };
+    let error = rustc_internal::internal_checked(tcx, &invalid, &()).unwrap_err();
+    assert!(error.to_string().contains("does not fit"), "Unexpected error: {error}");
+    ControlFlow::Continue(())
+}
+
+fn get_item<'a>(items: &'a CrateItems, name: &str) -> Option<&'a stable_mir::CrateItem> {
+    items.iter().find(|crate_item| crate_item.name() == name)
+}
+
+/// This test will generate and analyze a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// Then it will create a `StableMir` using custom arguments and then
+/// it will run the compiler.
+fn main() {
+    let path = "internal_scalar_input.rs";
+    generate_input(&path).unwrap();
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.to_string(),
+    ];
+    run_with_tcx!(args, test_scalar).unwrap();
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+    pub fn get(value: Option<&u8>) -> u8 {{
+        value.copied().unwrap_or_default()
+    }}
+    "#
+    )?;
+    Ok(())
+}
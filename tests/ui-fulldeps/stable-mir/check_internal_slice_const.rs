@@ -0,0 +1,119 @@
+//@ run-pass
+//! Test that slice constants, such as string literals, can be built from a stable allocation and
+//! their length.
+
+//@ ignore-stage1
+//@ ignore-cross-compile
+//@ ignore-remote
+//@ ignore-windows-gnu mingw has troubles with linking https://github.com/rust-lang/rust/pull/116837
+//@ edition: 2021
+
+#![feature(rustc_private)]
+#![feature(assert_matches)]
+#![feature(control_flow_enum)]
+
+#[macro_use]
+extern crate rustc_smir;
+extern crate rustc_driver;
+extern crate rustc_interface;
+extern crate rustc_middle;
+extern crate stable_mir;
+
+use rustc_middle::mir::ConstValue;
+use rustc_middle::ty::TyCtxt;
+use rustc_smir::rustc_internal;
+use stable_mir::mir::{Mutability, Operand, Rvalue, StatementKind};
+use stable_mir::ty::{Allocation, MirConst, ProvenanceMap, Ty, UintTy};
+use stable_mir::CrateItems;
+use std::io::Write;
+use std::ops::ControlFlow;
+
+const CRATE_NAME: &str = "input";
+
+fn test_slice_const(tcx: TyCtxt<'_>) -> ControlFlow<()> {
+    let items = stable_mir::all_local_items();
+    let literal = find_constant(get_item(&items, "greet").unwrap());
+
+    // `"hi"`
+    let mut alloc = Allocation {
+        bytes: "hi".bytes().map(Some).collect(),
+        provenance: ProvenanceMap { ptrs: vec![] },
+        align: 1,
+        mutability: Mutability::Not,
+    };
+    let u8_ty = Ty::unsigned_ty(UintTy::U8);
+    let internal = rustc_internal::internal_slice_const(tcx, &alloc, u8_ty, 2).unwrap();
+    let ConstValue::Slice { data, meta } = internal else { unreachable!() };
+    assert_eq!(meta, 2);
+    assert_eq!(data.inner().inspect_with_uninit_and_ptr_outside_interpreter(0..2), b"hi");
+
+    // Allocations are interned, so the constant must be the same as the one of the literal.
+    let rustc_middle::mir::Const::Val(expected, ty) = rustc_internal::internal(tcx, &literal)
+    else {
+        unreachable!("Expected a value constant, but found: {literal:?}")
+    };
+    assert_eq!(internal, expected);
+    assert_eq!(ty, rustc_middle::ty::Ty::new_static_str(tcx));
+
+    // The two bytes only fit two `u8` elements, or a single `u16` element.
+    let error = rustc_internal::internal_slice_const(tcx, &alloc, u8_ty, 3).unwrap_err();
+    assert!(error.to_string().contains("does not fit"), "Unexpected error: {error}");
+    let u16_ty = Ty::unsigned_ty(UintTy::U16);
+    assert!(rustc_internal::internal_slice_const(tcx, &alloc, u16_ty, 1).is_ok());
+    let error = rustc_internal::internal_slice_const(tcx, &alloc, u16_ty, 2).unwrap_err();
+    assert!(error.to_string().contains("does not fit"), "Unexpected error: {error}");
+
+    alloc.align = 3;
+    let error = rustc_internal::internal_slice_const(tcx, &alloc, u8_ty, 2).unwrap_err();
+    assert!(error.to_string().contains("Invalid alignment"), "Unexpected error: {error}");
+    ControlFlow::Continue(())
+}
+
+/// Find the constant assigned in the given function.
+fn find_constant(item: &stable_mir::CrateItem) -> MirConst {
+    item.body()
+        .blocks
+        .iter()
+        .flat_map(|block| &block.statements)
+        .find_map(|statement| match &statement.kind {
+            StatementKind::Assign(_, Rvalue::Use(Operand::Constant(constant))) => {
+                Some(constant.const_.clone())
+            }
+            _ => None,
+        })
+        .unwrap()
+}
+
+fn get_item<'a>(items: &'a CrateItems, name: &str) -> Option<&'a stable_mir::CrateItem> {
+    items.iter().find(|crate_item| crate_item.name() == name)
+}
+
+/// This test will generate and analyze a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// Then it will create a `StableMir` using custom arguments and then
+/// it will run the compiler.
+fn main() {
+    let path = "internal_slice_const_input.rs";
+    generate_input(&path).unwrap();
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.to_string(),
+    ];
+    run_with_tcx!(args, test_slice_const).unwrap();
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+    pub fn greet() -> &'static str {{
+        "hi"
+    }}
+    "#
+    )?;
+    Ok(())
+}
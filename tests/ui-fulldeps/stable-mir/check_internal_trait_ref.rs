@@ -0,0 +1,92 @@
+//@ run-pass
+//! Test that the arguments of a trait reference are validated when converting it to its internal
+//! representation, including that the `Self` type comes first.
+
+//@ ignore-stage1
+//@ ignore-cross-compile
+//@ ignore-remote
+//@ ignore-windows-gnu mingw has troubles with linking https://github.com/rust-lang/rust/pull/116837
+//@ edition: 2021
+
+#![feature(rustc_private)]
+#![feature(assert_matches)]
+#![feature(control_flow_enum)]
+
+#[macro_use]
+extern crate rustc_smir;
+extern crate rustc_driver;
+extern crate rustc_interface;
+extern crate rustc_middle;
+extern crate stable_mir;
+
+use rustc_middle::ty::TyCtxt;
+use rustc_smir::rustc_internal;
+use stable_mir::crate_def::CrateDef;
+use stable_mir::ty::{GenericArgKind, GenericArgs, RigidTy, TraitRef, Ty};
+use stable_mir::CrateItems;
+use std::io::Write;
+use std::ops::ControlFlow;
+
+const CRATE_NAME: &str = "input";
+
+fn test_trait_ref(tcx: TyCtxt<'_>) -> ControlFlow<()> {
+    let items = stable_mir::all_local_items();
+    let string = get_item(&items, "convert").unwrap().body().locals()[1].ty;
+    let str_ty = Ty::from_rigid_kind(RigidTy::Str);
+    let as_ref = stable_mir::all_trait_decls()
+        .into_iter()
+        .find(|def| def.trimmed_name() == "AsRef")
+        .unwrap();
+
+    // `String: AsRef<str>`
+    let args = GenericArgs(vec![GenericArgKind::Type(str_ty)]);
+    let trait_ref = TraitRef::new(as_ref, string, &args);
+    let internal = rustc_internal::internal_checked(tcx, &trait_ref, &()).unwrap();
+    assert_eq!(internal, rustc_internal::internal(tcx, &trait_ref));
+    assert_eq!(internal.self_ty(), rustc_internal::internal(tcx, string));
+    assert_eq!(internal.args.type_at(1), tcx.types.str_);
+
+    // The `Self` type cannot be omitted.
+    let missing_self = TraitRef::try_new(as_ref, args).unwrap();
+    let error = rustc_internal::internal_checked(tcx, &missing_self, &()).unwrap_err();
+    assert!(
+        error.to_string().contains("`Self` type must be the first argument"),
+        "Unexpected error: {error}"
+    );
+    ControlFlow::Continue(())
+}
+
+fn get_item<'a>(items: &'a CrateItems, name: &str) -> Option<&'a stable_mir::CrateItem> {
+    items.iter().find(|crate_item| crate_item.name() == name)
+}
+
+/// This test will generate and analyze a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// Then it will create a `StableMir` using custom arguments and then
+/// it will run the compiler.
+fn main() {
+    let path = "internal_trait_ref_input.rs";
+    generate_input(&path).unwrap();
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.to_string(),
+    ];
+    run_with_tcx!(args, test_trait_ref).unwrap();
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+    pub fn convert(value: String) -> usize {{
+        let text: &str = value.as_ref();
+        text.len()
+    }}
+    "#
+    )?;
+    Ok(())
+}
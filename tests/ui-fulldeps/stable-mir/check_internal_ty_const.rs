@@ -0,0 +1,95 @@
+//@ run-pass
+//! Test that an unevaluated type system constant can be built from its definition and generic
+//! arguments.
+
+//@ ignore-stage1
+//@ ignore-cross-compile
+//@ ignore-remote
+//@ ignore-windows-gnu mingw has troubles with linking https://github.com/rust-lang/rust/pull/116837
+//@ edition: 2021
+
+#![feature(rustc_private)]
+#![feature(assert_matches)]
+#![feature(control_flow_enum)]
+
+#[macro_use]
+extern crate rustc_smir;
+extern crate rustc_driver;
+extern crate rustc_interface;
+extern crate rustc_middle;
+extern crate stable_mir;
+
+use rustc_middle::ty::TyCtxt;
+use rustc_smir::rustc_internal;
+use stable_mir::ty::{GenericArgs, RigidTy, TyConstKind, TyKind};
+use stable_mir::CrateItems;
+use std::io::Write;
+use std::ops::ControlFlow;
+
+const CRATE_NAME: &str = "input";
+
+fn test_ty_const(tcx: TyCtxt<'_>) -> ControlFlow<()> {
+    let items = stable_mir::all_local_items();
+    let body = get_item(&items, "next").unwrap().body();
+    let ret_ty = body.locals()[0].ty;
+    let TyKind::RigidTy(RigidTy::Array(_, len)) = ret_ty.kind() else {
+        unreachable!("Expected an array, but found: {ret_ty:?}")
+    };
+    // `{ N + 1 }`
+    let TyConstKind::Unevaluated(def, args) = len.kind() else {
+        unreachable!("Expected an unevaluated constant, but found: {len:?}")
+    };
+    let kind = TyConstKind::Unevaluated(*def, args.clone());
+    let internal = rustc_internal::internal_checked(tcx, &kind, &()).unwrap();
+    assert_eq!(internal, rustc_internal::internal(tcx, &len));
+
+    // The arguments must match the definition of the constant.
+    let invalid = TyConstKind::Unevaluated(*def, GenericArgs(vec![]));
+    let error = rustc_internal::internal_checked(tcx, &invalid, &()).unwrap_err();
+    assert!(error.to_string().contains("Expected 1 generic"), "Unexpected error: {error}");
+
+    // Other kinds of constants cannot be built from their kind.
+    let value = stable_mir::ty::TyConst::try_from_target_usize(1).unwrap();
+    let error = rustc_internal::internal_checked(tcx, value.kind(), &()).unwrap_err();
+    assert!(error.to_string().contains("Expected an unevaluated"), "Unexpected error: {error}");
+    ControlFlow::Continue(())
+}
+
+fn get_item<'a>(items: &'a CrateItems, name: &str) -> Option<&'a stable_mir::CrateItem> {
+    items.iter().find(|crate_item| crate_item.name() == name)
+}
+
+/// This test will generate and analyze a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// Then it will create a `StableMir` using custom arguments and then
+/// it will run the compiler.
+fn main() {
+    let path = "internal_ty_const_input.rs";
+    generate_input(&path).unwrap();
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.to_string(),
+    ];
+    run_with_tcx!(args, test_ty_const).unwrap();
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+    #![feature(generic_const_exprs)]
+    #![allow(incomplete_features)]
+
+    pub fn next<const N: usize>(bytes: [u8; N]) -> [u8; N + 1] {{
+        let mut next = [0; N + 1];
+        next[..N].copy_from_slice(&bytes);
+        next
+    }}
+    "#
+    )?;
+    Ok(())
+}
@@ -0,0 +1,107 @@
+//@ run-pass
+//! Test that a variant can be converted to its internal representation together with its
+//! discriminant and its fields.
+
+//@ ignore-stage1
+//@ ignore-cross-compile
+//@ ignore-remote
+//@ ignore-windows-gnu mingw has troubles with linking https://github.com/rust-lang/rust/pull/116837
+//@ edition: 2021
+
+#![feature(rustc_private)]
+#![feature(assert_matches)]
+#![feature(control_flow_enum)]
+
+#[macro_use]
+extern crate rustc_smir;
+extern crate rustc_driver;
+extern crate rustc_interface;
+extern crate rustc_middle;
+extern crate stable_mir;
+
+use rustc_middle::ty::TyCtxt;
+use rustc_smir::rustc_internal;
+use stable_mir::ty::{AdtDef, RigidTy, TyKind};
+use stable_mir::CrateItems;
+use std::io::Write;
+use std::ops::ControlFlow;
+
+const CRATE_NAME: &str = "input";
+
+fn test_variant(tcx: TyCtxt<'_>) -> ControlFlow<()> {
+    let items = stable_mir::all_local_items();
+    let body = get_item(&items, "area").unwrap().body();
+
+    let shape = adt_def(&body.locals()[1].ty.kind());
+    let rect = shape.variants_iter().find(|variant| variant.name() == "Rect").unwrap();
+    let internal = rustc_internal::internal_variant(tcx, rect);
+    assert_eq!(internal.def.name.as_str(), "Rect");
+    assert_eq!(internal.fields.len(), 2);
+    let discr = internal.discr.unwrap();
+    assert_eq!(discr.val, 7);
+    assert_eq!(discr.ty, tcx.types.u8);
+
+    let point = shape.variants_iter().find(|variant| variant.name() == "Point").unwrap();
+    let internal = rustc_internal::internal_variant(tcx, point);
+    assert!(internal.fields.is_empty());
+    assert_eq!(internal.discr.unwrap().val, 3);
+
+    // Struct variants have no discriminant.
+    let scale = adt_def(&body.locals()[2].ty.kind());
+    let internal = rustc_internal::internal_variant(tcx, scale.variants_iter().next().unwrap());
+    assert!(internal.discr.is_none());
+    assert_eq!(internal.fields.len(), 1);
+    ControlFlow::Continue(())
+}
+
+fn adt_def(kind: &TyKind) -> AdtDef {
+    let TyKind::RigidTy(RigidTy::Adt(def, _)) = kind else {
+        unreachable!("Expected an ADT, but found: {kind:?}")
+    };
+    *def
+}
+
+fn get_item<'a>(items: &'a CrateItems, name: &str) -> Option<&'a stable_mir::CrateItem> {
+    items.iter().find(|crate_item| crate_item.name() == name)
+}
+
+/// This test will generate and analyze a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// Then it will create a `StableMir` using custom arguments and then
+/// it will run the compiler.
+fn main() {
+    let path = "internal_variant_input.rs";
+    generate_input(&path).unwrap();
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.to_string(),
+    ];
+    run_with_tcx!(args, test_variant).unwrap();
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+    #[repr(u8)]
+    pub enum Shape {{
+        Point = 3,
+        Rect(u32, u32) = 7,
+    }}
+
+    pub struct Scale(pub u32);
+
+    pub fn area(shape: Shape, scale: Scale) -> u32 {{
+        match shape {{
+            Shape::Point => 0,
+            Shape::Rect(width, height) => width * height * scale.0,
+        }}
+    }}
+    "#
+    )?;
+    Ok(())
+}
@@ -0,0 +1,136 @@
+//@ run-pass
+//! Test that the variants of a layout can be converted to their internal representation,
+//! including how the tag of the enum is encoded.
+
+//@ ignore-stage1
+//@ ignore-cross-compile
+//@ ignore-remote
+//@ ignore-windows-gnu mingw has troubles with linking https://github.com/rust-lang/rust/pull/116837
+//@ edition: 2021
+
+#![feature(rustc_private)]
+#![feature(assert_matches)]
+#![feature(control_flow_enum)]
+
+#[macro_use]
+extern crate rustc_smir;
+extern crate rustc_abi;
+extern crate rustc_driver;
+extern crate rustc_interface;
+extern crate rustc_middle;
+extern crate stable_mir;
+
+use rustc_middle::ty::TyCtxt;
+use rustc_smir::rustc_internal;
+use stable_mir::abi::{TagEncoding, VariantsShape};
+use stable_mir::ty::IndexedVal;
+use stable_mir::CrateItems;
+use std::assert_matches::assert_matches;
+use std::io::Write;
+use std::ops::ControlFlow;
+
+const CRATE_NAME: &str = "input";
+
+fn test_variants(tcx: TyCtxt<'_>) -> ControlFlow<()> {
+    let items = stable_mir::all_local_items();
+    let locals = get_item(&items, "encode").unwrap().body().locals().to_vec();
+    let niche = check_variants(tcx, locals[1].ty);
+    let VariantsShape::Multiple { tag_encoding: TagEncoding::Niche { niche_start, .. }, .. } =
+        &niche
+    else {
+        unreachable!("Expected niche encoded variants, but found: {niche:?}")
+    };
+    // `None` is stored in the first invalid value of `bool`.
+    assert_eq!(*niche_start, 2);
+
+    let direct = check_variants(tcx, locals[2].ty);
+    assert_matches!(direct, VariantsShape::Multiple { tag_encoding: TagEncoding::Direct, .. });
+
+    // The variants must match the layout they are converted with.
+    let layout = locals[3].ty.layout().unwrap();
+    let error = rustc_internal::internal_checked(tcx, &niche, &layout).unwrap_err();
+    assert!(error.to_string().contains("do not match"), "Unexpected error: {error}");
+
+    check_non_zero(tcx, get_item(&items, "non_zero").unwrap());
+    ControlFlow::Continue(())
+}
+
+/// Check that the niche encoding of `Option<NonZeroU32>` survives the conversion.
+fn check_non_zero(tcx: TyCtxt<'_>, item: &stable_mir::CrateItem) {
+    let layout = item.body().locals()[1].ty.layout().unwrap();
+    let VariantsShape::Multiple { tag_encoding, .. } = layout.shape().variants else {
+        unreachable!("Expected multiple variants")
+    };
+    let TagEncoding::Niche { untagged_variant, niche_variants, niche_start } = &tag_encoding else {
+        unreachable!("Expected a niche encoding, but found: {tag_encoding:?}")
+    };
+    // `None` is stored as `0`, which is not a valid value of `Some`.
+    assert_eq!(untagged_variant.to_index(), 1);
+    assert_eq!((niche_variants.start().to_index(), niche_variants.end().to_index()), (0, 0));
+    assert_eq!(*niche_start, 0);
+
+    let internal = rustc_internal::internal(tcx, &tag_encoding);
+    let rustc_abi::Variants::Multiple { tag_encoding: expected, .. } =
+        rustc_internal::internal(tcx, layout).variants()
+    else {
+        unreachable!()
+    };
+    assert_eq!(&internal, expected);
+    assert_eq!(rustc_internal::stable(&internal), tag_encoding);
+}
+
+/// Check that the variants of the layout of the given type survive a round trip.
+fn check_variants(tcx: TyCtxt<'_>, ty: stable_mir::ty::Ty) -> VariantsShape {
+    let layout = ty.layout().unwrap();
+    let variants = layout.shape().variants;
+    let internal = rustc_internal::internal_checked(tcx, &variants, &layout).unwrap();
+    assert_eq!(&internal, rustc_internal::internal(tcx, layout).variants());
+    assert_eq!(rustc_internal::stable(&internal), variants);
+    variants
+}
+
+fn get_item<'a>(items: &'a CrateItems, name: &str) -> Option<&'a stable_mir::CrateItem> {
+    items.iter().find(|crate_item| crate_item.name() == name)
+}
+
+/// This test will generate and analyze a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// Then it will create a `StableMir` using custom arguments and then
+/// it will run the compiler.
+fn main() {
+    let path = "internal_variants_input.rs";
+    generate_input(&path).unwrap();
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.to_string(),
+    ];
+    run_with_tcx!(args, test_variants).unwrap();
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+    pub enum Tagged {{
+        First(u32),
+        Second(u32),
+    }}
+
+    pub fn non_zero(value: Option<std::num::NonZeroU32>) -> u32 {{
+        value.map_or(0, |value| value.get())
+    }}
+
+    pub fn encode(niche: Option<bool>, direct: Tagged, value: u8) -> u8 {{
+        match (niche, direct) {{
+            (Some(true), Tagged::First(_)) => value,
+            _ => 0,
+        }}
+    }}
+    "#
+    )?;
+    Ok(())
+}